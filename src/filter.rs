@@ -1,29 +1,78 @@
 use std::io::Seek;
 
 use arpeggiation::Arpeggiation;
+use biquad::{Biquad, BiquadKind};
+use bitcrusher::Bitcrusher;
+use chorus::Chorus;
+use compressor::Compressor;
+use convolution::{Convolution, ImpulseResponse};
+use delay::Delay;
 use envelope::Envelope;
+use exponential_slide::ExponentialSlide;
 use flanger::Flanger;
 use frequency_slide::FrequencySlide;
 use highpass::Highpass;
+use limiter::Limiter;
 use lowpass::Lowpass;
+use max_frequency::MaxFrequency;
 use min_cutoff::MinCutoff;
+use noise_gate::NoiseGate;
+use phaser::Phaser;
+use repeat::Repeat;
+use resonant_highpass::ResonantHighpass;
+use resonant_lowpass::ResonantLowpass;
 use retrigger::Retrigger;
+use reverb::Reverb;
+use ring_mod::RingMod;
+use safeguard::Safeguard;
+use sample_and_hold::SampleAndHold;
+use state_variable::StateVariableFilter;
+use tremolo::{Tremolo, TremoloShape};
 use vibrato::Vibrato;
+use waveshaper::{Shape, Waveshaper};
+use widener::Widener;
 
 use crate::{
-    wave::{SawtoothWaveGenerator, SineWaveGenerator, SquareWaveGenerator},
-    Amplitude, Frequency, Sample,
+    modulation::Modulation,
+    wave::{
+        BitNoiseGenerator, BreakerWaveGenerator, BrownNoiseGenerator, NoiseWaveGenerator,
+        PinkNoiseGenerator, SawtoothWaveGenerator, SineWaveGenerator, SquareWaveGenerator,
+        TanWaveGenerator, TriangleWaveGenerator, WhistleWaveGenerator,
+    },
+    Amplitude, Frequency, Sample, Stereo,
 };
 
 pub mod arpeggiation;
+pub mod biquad;
+pub mod bitcrusher;
+pub mod chorus;
+pub mod compressor;
+pub mod convolution;
+pub mod delay;
 pub mod envelope;
+pub mod exponential_slide;
 pub mod flanger;
 pub mod frequency_slide;
 pub mod highpass;
+pub mod limiter;
 pub mod lowpass;
+pub mod max_frequency;
 pub mod min_cutoff;
+pub mod noise_gate;
+pub mod phaser;
+pub mod repeat;
+pub mod resonant_highpass;
+pub mod resonant_lowpass;
 pub mod retrigger;
+pub mod reverb;
+pub mod ring_mod;
+pub mod safeguard;
+pub mod sample_and_hold;
+pub mod state_variable;
+pub mod tremolo;
 pub mod vibrato;
+pub mod waveshaper;
+pub mod widener;
 
 /// Contains filters which can be applied to iterators of frequency samples, i.e., to
 /// `Iterator<Item = Sample<Frequency>>`
@@ -39,6 +88,17 @@ pub trait FrequencyDomainFilterable {
         }
     }
 
+    /// Keeps the frequency at or below `max_frequency`, higher frequencies are replaced by it.
+    fn max_frequency(self, max_frequency: f64) -> MaxFrequency<Self>
+    where
+        Self: Sized,
+    {
+        MaxFrequency {
+            generator: self,
+            max_frequency,
+        }
+    }
+
     /// Adds `frequency_slide` to the frequency on every call to `next()`.
     /// Adds `frequency_slide_delta` to the frequency_slide on every call to `next()`.
     fn slide(
@@ -57,6 +117,25 @@ pub trait FrequencyDomainFilterable {
         }
     }
 
+    /// Multiplies the frequency by `e^slide` every second, like sfxr's slide.
+    /// Adds `slide_delta` to the slide every second.
+    fn exponential_slide(
+        self,
+        slide: f64,
+        slide_delta: f64,
+        sample_rate: u64,
+    ) -> ExponentialSlide<Self>
+    where
+        Self: Sized,
+    {
+        ExponentialSlide {
+            generator: self,
+            slide,
+            slide_delta,
+            sample_rate,
+        }
+    }
+
     /// Does a sin wave on the frequency.
     fn vibrato(self, vibrato_speed: f64, vibrato_depth: f64, sample_rate: u64) -> Vibrato<Self>
     where
@@ -83,6 +162,21 @@ pub trait FrequencyDomainFilterable {
         }
     }
 
+    /// Restarts the stream from the beginning `rate` times per second, keeping the phase going.
+    fn repeat(self, rate: f64, sample_rate: u64) -> Repeat<Self>
+    where
+        Self: Sized + Seek,
+    {
+        Repeat::new(
+            self,
+            if rate > 0.0 {
+                (sample_rate as f64 / rate).round() as u64
+            } else {
+                0
+            },
+        )
+    }
+
     /// Generates `num_triggers` new streams identical to current one, but from the beginning, and
     /// adds them to the current one
     fn retrigger(self, rate: f64, num_retriggers: u64, sample_rate: u64) -> Retrigger<Self>
@@ -100,39 +194,103 @@ pub trait FrequencyDomainFilterable {
     }
 
     /// Converts a stream of frequency samples to amplitude samples, using a square wave.
-    fn square_wave(self, sample_rate: u64, duty_cycle: f64) -> SquareWaveGenerator<Self>
+    /// `duty_cycle` is either a fixed `f64` or any [`Modulation`], e.g. a sweep.
+    fn square_wave<D: Modulation>(
+        self,
+        sample_rate: u64,
+        duty_cycle: D,
+    ) -> SquareWaveGenerator<Self, D>
     where
         Self: Sized,
     {
-        SquareWaveGenerator {
-            generator: self,
-            sample_rate,
-            duty_cycle,
-        }
+        SquareWaveGenerator::new(self, sample_rate, duty_cycle)
     }
 
     /// Converts a stream of frequency samples to amplitude samples, using a sine wave.
-    fn sine_wave(self, sample_rate: u64, duty_cycle: f64) -> SineWaveGenerator<Self>
+    /// `duty_cycle` is either a fixed `f64` or any [`Modulation`], e.g. a sweep.
+    fn sine_wave<D: Modulation>(self, sample_rate: u64, duty_cycle: D) -> SineWaveGenerator<Self, D>
     where
         Self: Sized,
     {
-        SineWaveGenerator {
-            generator: self,
-            sample_rate,
-            duty_cycle,
-        }
+        SineWaveGenerator::new(self, sample_rate, duty_cycle)
     }
 
     /// Converts a stream of frequency samples to amplitude samples, using a sawtooth wave.
-    fn sawtooth_wave(self, sample_rate: u64, duty_cycle: f64) -> SawtoothWaveGenerator<Self>
+    /// `duty_cycle` is either a fixed `f64` or any [`Modulation`], e.g. a sweep.
+    fn sawtooth_wave<D: Modulation>(
+        self,
+        sample_rate: u64,
+        duty_cycle: D,
+    ) -> SawtoothWaveGenerator<Self, D>
     where
         Self: Sized,
     {
-        SawtoothWaveGenerator {
-            generator: self,
-            sample_rate,
-            duty_cycle,
-        }
+        SawtoothWaveGenerator::new(self, sample_rate, duty_cycle)
+    }
+
+    /// Converts a stream of frequency samples to amplitude samples, using a triangle wave.
+    fn triangle_wave(self, sample_rate: u64) -> TriangleWaveGenerator<Self>
+    where
+        Self: Sized,
+    {
+        TriangleWaveGenerator::new(self, sample_rate)
+    }
+
+    /// Converts a stream of frequency samples to amplitude samples, using bfxr's breaker wave.
+    fn breaker_wave(self, sample_rate: u64) -> BreakerWaveGenerator<Self>
+    where
+        Self: Sized,
+    {
+        BreakerWaveGenerator::new(self, sample_rate)
+    }
+
+    /// Converts a stream of frequency samples to amplitude samples, using a clipped tangent wave.
+    fn tan_wave(self, sample_rate: u64) -> TanWaveGenerator<Self>
+    where
+        Self: Sized,
+    {
+        TanWaveGenerator::new(self, sample_rate)
+    }
+
+    /// Converts a stream of frequency samples to amplitude samples, using a sine wave with a
+    /// quieter 20th harmonic.
+    fn whistle_wave(self, sample_rate: u64) -> WhistleWaveGenerator<Self>
+    where
+        Self: Sized,
+    {
+        WhistleWaveGenerator::new(self, sample_rate)
+    }
+
+    /// Converts a stream of frequency samples to white noise, with a new value every period.
+    fn noise_wave(self, sample_rate: u64, seed: u64) -> NoiseWaveGenerator<Self>
+    where
+        Self: Sized,
+    {
+        NoiseWaveGenerator::new(self, sample_rate, seed)
+    }
+
+    /// Converts a stream of frequency samples to pink noise, with a new value every period.
+    fn pink_noise(self, sample_rate: u64, seed: u64) -> PinkNoiseGenerator<Self>
+    where
+        Self: Sized,
+    {
+        PinkNoiseGenerator::new(self, sample_rate, seed)
+    }
+
+    /// Converts a stream of frequency samples to brown noise, with a new value every period.
+    fn brown_noise(self, sample_rate: u64, seed: u64) -> BrownNoiseGenerator<Self>
+    where
+        Self: Sized,
+    {
+        BrownNoiseGenerator::new(self, sample_rate, seed)
+    }
+
+    /// Converts a stream of frequency samples to 1-bit noise, with a new value every period.
+    fn bit_noise(self, sample_rate: u64) -> BitNoiseGenerator<Self>
+    where
+        Self: Sized,
+    {
+        BitNoiseGenerator::new(self, sample_rate)
     }
 }
 
@@ -169,7 +327,206 @@ pub trait AmplitudeDomainFilterable {
         }
     }
 
-    /// Gradual increase and decrease to the amplitude
+    /// Adds a copy of the stream from a delay line whose delay sweeps over time, like sfxr's
+    /// phaser. The delay starts at `delay` seconds, changes by `sweep` seconds every second and
+    /// stays under `max_delay` seconds.
+    fn phaser(
+        self,
+        delay: f64,
+        sweep: f64,
+        max_delay: f64,
+        feedback: f64,
+        mix: f64,
+        sample_rate: u64,
+    ) -> Phaser<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Phaser::new(self, delay, sweep, max_delay, feedback, mix, sample_rate)
+    }
+
+    /// Echoes the stream every `time` seconds, see [`delay::beats`] for tempo synced times.
+    /// Keeps going after the stream ends until the echoes die out.
+    fn delay(self, time: f64, feedback: f64, mix: f64, sample_rate: u64) -> Delay<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Delay::new(self, time, feedback, mix, sample_rate)
+    }
+
+    /// Freeverb reverb, `room_size`, `damping` and `width` go from `0.0` to `1.0`. Outputs a
+    /// stereo stream and keeps going after the stream ends until the reverb dies out.
+    fn reverb(
+        self,
+        room_size: f64,
+        damping: f64,
+        width: f64,
+        wet: f64,
+        dry: f64,
+        sample_rate: u64,
+    ) -> Reverb<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Reverb::new(self, room_size, damping, width, wet, dry, sample_rate)
+    }
+
+    /// Convolves the stream with an impulse response, e.g. a recorded room loaded with
+    /// [`ImpulseResponse::load`] or a `Vec<f64>`.
+    fn convolve<I>(self, impulse_response: I) -> Convolution<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        I: Into<ImpulseResponse>,
+    {
+        Convolution::new(self, impulse_response)
+    }
+
+    /// Distorts the stream with `shape` after multiplying it by `drive`, then multiplies it by
+    /// `output_gain`.
+    fn waveshaper(
+        self,
+        shape: Shape,
+        drive: f64,
+        output_gain: f64,
+        sample_rate: u64,
+    ) -> Waveshaper<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Waveshaper::new(self, shape, drive, output_gain, sample_rate)
+    }
+
+    /// Reduces the stream to `bits` bits, either a fixed `f64` or any [`Modulation`]. Add dither
+    /// with [`Bitcrusher::dithered`].
+    fn bitcrush<B>(self, bits: B) -> Bitcrusher<Self, B>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        B: Modulation,
+    {
+        Bitcrusher::new(self, bits)
+    }
+
+    /// Holds each value of the stream until the next of `rate` values per second, `rate` is
+    /// either a fixed `f64` or any [`Modulation`].
+    fn sample_and_hold<R>(self, rate: R, sample_rate: u64) -> SampleAndHold<Self, R>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        R: Modulation,
+    {
+        SampleAndHold::new(self, rate, sample_rate)
+    }
+
+    /// Turns down everything over `threshold` dB by `ratio`, with `attack` and `release` in
+    /// seconds and a soft `knee` in dB.
+    fn compress(
+        self,
+        threshold: f64,
+        ratio: f64,
+        attack: f64,
+        release: f64,
+        knee: f64,
+        sample_rate: u64,
+    ) -> Compressor<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Compressor::new(self, threshold, ratio, attack, release, knee, sample_rate)
+    }
+
+    /// Keeps every sample under `ceiling` dB, turning the gain down `lookahead` seconds before a
+    /// peak and back up over `release` seconds.
+    fn limit(self, ceiling: f64, lookahead: f64, release: f64, sample_rate: u64) -> Limiter<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Limiter::new(self, ceiling, lookahead, release, sample_rate)
+    }
+
+    /// Silences the stream while it is under `threshold` dB, opening over `attack` seconds and
+    /// closing over `release` seconds.
+    fn noise_gate(
+        self,
+        threshold: f64,
+        attack: f64,
+        release: f64,
+        sample_rate: u64,
+    ) -> NoiseGate<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        NoiseGate::new(self, threshold, attack, release, sample_rate)
+    }
+
+    /// Replaces NaN and infinite samples with silence and clips to `-1.0..=1.0`, counting what it
+    /// corrected. Meant to be the last filter before a sink.
+    fn safeguard(self, sample_rate: u64) -> Safeguard<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Safeguard::new(self, sample_rate)
+    }
+
+    /// Varies the volume `rate` times per second, `depth` from `0.0` for no change to `1.0` for
+    /// silence at the quietest point.
+    fn tremolo(self, rate: f64, depth: f64, shape: TremoloShape, sample_rate: u64) -> Tremolo<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Tremolo {
+            generator: self,
+            rate,
+            depth,
+            shape,
+            sample_rate,
+        }
+    }
+
+    /// Multiplies the stream by `carrier`, any other stream of amplitude samples, e.g. a sine
+    /// wave.
+    fn ring_mod<C>(self, carrier: C) -> RingMod<Self, C>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        C: Iterator<Item = Sample<Amplitude>>,
+    {
+        RingMod {
+            generator: self,
+            carrier,
+        }
+    }
+
+    /// Adds `voices` copies of the stream, each delayed by a sway of `depth` seconds `rate` times
+    /// per second and spread from left to right, giving a stereo stream.
+    fn chorus(
+        self,
+        voices: usize,
+        rate: f64,
+        depth: f64,
+        mix: f64,
+        sample_rate: u64,
+    ) -> Chorus<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+    {
+        Chorus::new(self, voices, rate, depth, mix, sample_rate)
+    }
+
+    /// Gradual increase and decrease to the amplitude.
+    /// The sustain starts at `sustain_punch` and falls linearly to 1 for the decay, like sfxr's
+    /// punch.
     fn envelope(
         self,
         attack_time: f64,
@@ -199,13 +556,201 @@ pub trait AmplitudeDomainFilterable {
         Lowpass::new(self, cutoff, sample_rate)
     }
 
-    fn highpass(self, cutoff: f64, sample_rate: u64) -> Highpass<Self>
+    /// One pole high-pass filter, `cutoff` in Hz can either be a fixed `f64` or any
+    /// [`Modulation`], e.g. a sweep.
+    fn highpass<C: Modulation>(self, cutoff: C, sample_rate: u64) -> Highpass<Self, C>
     where
         Self: Sized,
         Self: Iterator<Item = Sample<Amplitude>>,
     {
         Highpass::new(self, cutoff, sample_rate)
     }
+
+    /// Two pole low-pass filter, `cutoff` in Hz and `resonance` as the Q factor can either be
+    /// fixed `f64`s or any [`Modulation`], e.g. a sweep.
+    fn resonant_lowpass<C, R>(
+        self,
+        cutoff: C,
+        resonance: R,
+        sample_rate: u64,
+    ) -> ResonantLowpass<Self, C, R>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        C: Modulation,
+        R: Modulation,
+    {
+        ResonantLowpass::new(self, cutoff, resonance, sample_rate)
+    }
+
+    /// Two pole high-pass filter, `cutoff` in Hz and `resonance` as the Q factor can either be
+    /// fixed `f64`s or any [`Modulation`], e.g. a sweep.
+    fn resonant_highpass<C, R>(
+        self,
+        cutoff: C,
+        resonance: R,
+        sample_rate: u64,
+    ) -> ResonantHighpass<Self, C, R>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        C: Modulation,
+        R: Modulation,
+    {
+        ResonantHighpass::new(self, cutoff, resonance, sample_rate)
+    }
+
+    /// Any of the [`BiquadKind`] filters, `frequency` in Hz, `q` and `gain` in dB can either be
+    /// fixed `f64`s or any [`Modulation`].
+    fn biquad<F, Q, G>(
+        self,
+        kind: BiquadKind,
+        frequency: F,
+        q: Q,
+        gain: G,
+        sample_rate: u64,
+    ) -> Biquad<Self, F, Q, G>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+        G: Modulation,
+    {
+        Biquad::new(self, kind, frequency, q, gain, sample_rate)
+    }
+
+    /// State-variable filter with every output at once, its cutoff follows the stream of
+    /// frequency samples `cutoff`, e.g. a slide or a vibrato. Pick one output with
+    /// [`StateVariableFilter::output`].
+    fn state_variable<C, R>(
+        self,
+        cutoff: C,
+        resonance: R,
+        sample_rate: u64,
+    ) -> StateVariableFilter<Self, C, R>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        C: Iterator<Item = Sample<Frequency>>,
+        R: Modulation,
+    {
+        StateVariableFilter::new(self, cutoff, resonance, sample_rate)
+    }
+
+    /// Passes a band around `frequency`, narrower for higher `q`.
+    fn bandpass<F, Q>(self, frequency: F, q: Q, sample_rate: u64) -> Biquad<Self, F, Q>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+    {
+        Biquad::new(self, BiquadKind::Bandpass, frequency, q, 0.0, sample_rate)
+    }
+
+    /// Removes a band around `frequency`, narrower for higher `q`.
+    fn notch<F, Q>(self, frequency: F, q: Q, sample_rate: u64) -> Biquad<Self, F, Q>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+    {
+        Biquad::new(self, BiquadKind::Notch, frequency, q, 0.0, sample_rate)
+    }
+
+    /// Boosts or cuts a band around `frequency` by `gain` dB.
+    fn peaking_eq<F, Q, G>(
+        self,
+        frequency: F,
+        q: Q,
+        gain: G,
+        sample_rate: u64,
+    ) -> Biquad<Self, F, Q, G>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+        G: Modulation,
+    {
+        Biquad::new(self, BiquadKind::Peaking, frequency, q, gain, sample_rate)
+    }
+
+    /// Boosts or cuts everything below `frequency` by `gain` dB.
+    fn low_shelf<F, Q, G>(
+        self,
+        frequency: F,
+        q: Q,
+        gain: G,
+        sample_rate: u64,
+    ) -> Biquad<Self, F, Q, G>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+        G: Modulation,
+    {
+        Biquad::new(self, BiquadKind::LowShelf, frequency, q, gain, sample_rate)
+    }
+
+    /// Boosts or cuts everything above `frequency` by `gain` dB.
+    fn high_shelf<F, Q, G>(
+        self,
+        frequency: F,
+        q: Q,
+        gain: G,
+        sample_rate: u64,
+    ) -> Biquad<Self, F, Q, G>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+        G: Modulation,
+    {
+        Biquad::new(self, BiquadKind::HighShelf, frequency, q, gain, sample_rate)
+    }
+
+    /// Shifts the phase around `frequency` without changing the level.
+    fn allpass<F, Q>(self, frequency: F, q: Q, sample_rate: u64) -> Biquad<Self, F, Q>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Amplitude>>,
+        F: Modulation,
+        Q: Modulation,
+    {
+        Biquad::new(self, BiquadKind::Allpass, frequency, q, 0.0, sample_rate)
+    }
 }
 
 impl<T: Iterator<Item = Sample<Amplitude>>> AmplitudeDomainFilterable for T {}
+
+/// Contains filters which can be applied to iterators of stereo samples, i.e., to
+/// `Iterator<Item = Sample<Stereo>>`
+pub trait StereoFilterable {
+    /// Makes the stream wider for `width` over `1.0`, narrower under it.
+    fn widen(self, width: f64) -> Widener<Self>
+    where
+        Self: Sized,
+    {
+        Widener {
+            generator: self,
+            width,
+        }
+    }
+
+    /// Replaces NaN and infinite samples with silence and clips both channels to `-1.0..=1.0`,
+    /// counting what it corrected. Meant to be the last filter before a sink.
+    fn safeguard(self, sample_rate: u64) -> Safeguard<Self>
+    where
+        Self: Sized,
+        Self: Iterator<Item = Sample<Stereo>>,
+    {
+        Safeguard::new(self, sample_rate)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Stereo>>> StereoFilterable for T {}
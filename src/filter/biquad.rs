@@ -0,0 +1,214 @@
+use std::{
+    f64::consts::TAU,
+    io::{Seek, SeekFrom},
+};
+
+use crate::{modulation::Modulation, Amplitude, Sample};
+
+/// The response of a [`Biquad`], from Robert Bristow-Johnson's Audio EQ Cookbook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BiquadKind {
+    Lowpass,
+    Highpass,
+    /// Constant 0 dB peak gain at the center frequency
+    Bandpass,
+    Notch,
+    /// Boosts or cuts by `gain` dB around the center frequency
+    Peaking,
+    /// Boosts or cuts by `gain` dB below the corner frequency
+    LowShelf,
+    /// Boosts or cuts by `gain` dB above the corner frequency
+    HighShelf,
+    /// Passes every frequency unchanged and shifts the phase around the center frequency
+    Allpass,
+}
+
+/// The coefficients of a biquad, normalized so that `a0` is `1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coefficients {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl Coefficients {
+    /// `frequency` is the cutoff, center or corner frequency in Hz and is kept below the Nyquist
+    /// frequency. `q` sets the resonance or bandwidth, `gain` in dB is only used by
+    /// [`BiquadKind::Peaking`] and the shelves.
+    pub fn design(kind: BiquadKind, frequency: f64, q: f64, gain: f64, sample_rate: u64) -> Self {
+        let frequency = frequency.clamp(1.0, sample_rate as f64 * 0.49);
+        let omega = TAU * frequency / sample_rate as f64;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2.0 * q.max(0.1));
+        let amplitude = 10f64.powf(gain / 40.0);
+        let shelf = 2.0 * amplitude.sqrt() * alpha;
+
+        let (b0, b1, b2, a0, a1, a2) = match kind {
+            BiquadKind::Lowpass => (
+                (1.0 - cos) / 2.0,
+                1.0 - cos,
+                (1.0 - cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::Highpass => (
+                (1.0 + cos) / 2.0,
+                -(1.0 + cos),
+                (1.0 + cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::Bandpass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BiquadKind::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BiquadKind::Peaking => (
+                1.0 + alpha * amplitude,
+                -2.0 * cos,
+                1.0 - alpha * amplitude,
+                1.0 + alpha / amplitude,
+                -2.0 * cos,
+                1.0 - alpha / amplitude,
+            ),
+            BiquadKind::LowShelf => (
+                amplitude * ((amplitude + 1.0) - (amplitude - 1.0) * cos + shelf),
+                2.0 * amplitude * ((amplitude - 1.0) - (amplitude + 1.0) * cos),
+                amplitude * ((amplitude + 1.0) - (amplitude - 1.0) * cos - shelf),
+                (amplitude + 1.0) + (amplitude - 1.0) * cos + shelf,
+                -2.0 * ((amplitude - 1.0) + (amplitude + 1.0) * cos),
+                (amplitude + 1.0) + (amplitude - 1.0) * cos - shelf,
+            ),
+            BiquadKind::HighShelf => (
+                amplitude * ((amplitude + 1.0) + (amplitude - 1.0) * cos + shelf),
+                -2.0 * amplitude * ((amplitude - 1.0) + (amplitude + 1.0) * cos),
+                amplitude * ((amplitude + 1.0) + (amplitude - 1.0) * cos - shelf),
+                (amplitude + 1.0) - (amplitude - 1.0) * cos + shelf,
+                2.0 * ((amplitude - 1.0) - (amplitude + 1.0) * cos),
+                (amplitude + 1.0) - (amplitude - 1.0) * cos - shelf,
+            ),
+            BiquadKind::Allpass => (
+                1.0 - alpha,
+                -2.0 * cos,
+                1.0 + alpha,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+        };
+
+        Coefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    /// The gain of the filter for a sine wave at `frequency` Hz, `1.0` passes it unchanged.
+    pub fn magnitude_response(&self, frequency: f64, sample_rate: u64) -> f64 {
+        let omega = TAU * frequency / sample_rate as f64;
+        // |b0 + b1 z^-1 + b2 z^-2| with z = e^(i omega)
+        let magnitude = |c0: f64, c1: f64, c2: f64| {
+            let real = c0 + c1 * omega.cos() + c2 * (2.0 * omega).cos();
+            let imaginary = c1 * omega.sin() + c2 * (2.0 * omega).sin();
+            real.hypot(imaginary)
+        };
+        magnitude(self.b0, self.b1, self.b2) / magnitude(1.0, self.a1, self.a2)
+    }
+}
+
+/// The last two inputs and outputs of a biquad, run as direct form I so the coefficients can
+/// change between samples without glitches.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DirectForm {
+    inputs: [f64; 2],
+    outputs: [f64; 2],
+}
+
+impl DirectForm {
+    pub(crate) fn process(&mut self, coefficients: &Coefficients, input: f64) -> f64 {
+        let output = coefficients.b0 * input
+            + coefficients.b1 * self.inputs[0]
+            + coefficients.b2 * self.inputs[1]
+            - coefficients.a1 * self.outputs[0]
+            - coefficients.a2 * self.outputs[1];
+        self.inputs = [input, self.inputs[0]];
+        self.outputs = [output, self.outputs[0]];
+        output
+    }
+}
+
+/// A two pole, two zero filter, whose coefficients are designed again on every sample so the
+/// frequency, Q and gain can all be modulated.
+#[derive(Clone, Copy)]
+pub struct Biquad<T, F = f64, Q = f64, G = f64> {
+    pub generator: T,
+    pub kind: BiquadKind,
+    /// Cutoff, center or corner frequency in Hz
+    pub frequency: F,
+    pub q: Q,
+    /// Gain in dB, for [`BiquadKind::Peaking`] and the shelves
+    pub gain: G,
+    pub sample_rate: u64,
+    state: DirectForm,
+}
+
+impl<T, F, Q, G> Biquad<T, F, Q, G> {
+    pub fn new(
+        generator: T,
+        kind: BiquadKind,
+        frequency: F,
+        q: Q,
+        gain: G,
+        sample_rate: u64,
+    ) -> Self {
+        Biquad {
+            generator,
+            kind,
+            frequency,
+            q,
+            gain,
+            sample_rate,
+            state: DirectForm::default(),
+        }
+    }
+}
+
+impl<T, F, Q, G> Iterator for Biquad<T, F, Q, G>
+where
+    T: Iterator<Item = Sample<Amplitude>>,
+    F: Modulation,
+    Q: Modulation,
+    G: Modulation,
+{
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let coefficients = Coefficients::design(
+                self.kind,
+                self.frequency.next_value(),
+                self.q.next_value(),
+                self.gain.next_value(),
+                self.sample_rate,
+            );
+            Sample {
+                data: Amplitude(self.state.process(&coefficients, *sample)),
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek, F: Modulation, Q: Modulation, G: Modulation> Seek for Biquad<T, F, Q, G> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.generator.seek(pos)?;
+        self.frequency.seek_to(position);
+        self.q.seek_to(position);
+        self.gain.seek_to(position);
+        Ok(position)
+    }
+}
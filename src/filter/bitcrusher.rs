@@ -0,0 +1,66 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{generator::noise, modulation::Modulation, Amplitude, Sample};
+
+/// Rounds every sample to the nearest of `2^bits` evenly spaced levels from `-1.0` to `1.0`, so
+/// 1 bit leaves only `-1.0` and `1.0`. Samples beyond that range get the outermost level.
+///
+/// `bits` doesn't have to be whole, so it can sweep smoothly. With dither, triangular noise of
+/// one level is added before rounding, which trades the harsh distortion for a quiet hiss.
+#[derive(Clone, Copy)]
+pub struct Bitcrusher<T, B = f64> {
+    pub generator: T,
+    pub bits: B,
+    /// Seed of the dither noise, `None` for no dither
+    pub dither: Option<u64>,
+}
+
+impl<T, B> Bitcrusher<T, B> {
+    pub fn new(generator: T, bits: B) -> Self {
+        Bitcrusher {
+            generator,
+            bits,
+            dither: None,
+        }
+    }
+
+    /// Adds dither from the noise stream `seed`, the same seed always gives the same noise.
+    pub fn dithered(self, seed: u64) -> Self {
+        Bitcrusher {
+            dither: Some(seed),
+            ..self
+        }
+    }
+}
+
+impl<T, B> Iterator for Bitcrusher<T, B>
+where
+    T: Iterator<Item = Sample<Amplitude>>,
+    B: Modulation,
+{
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let steps = 2f64.powf(self.bits.next_value().clamp(1.0, 32.0)) - 1.0;
+            let dither = self.dither.map_or(0.0, |seed| {
+                noise(seed, 2 * sample.phase) + noise(seed, 2 * sample.phase + 1) - 1.0
+            });
+            let level = ((*sample * 0.5 + 0.5) * steps + dither)
+                .round()
+                .clamp(0.0, steps.floor());
+            Sample {
+                data: Amplitude(level / steps * 2.0 - 1.0),
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek, B: Modulation> Seek for Bitcrusher<T, B> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.generator.seek(pos)?;
+        self.bits.seek_to(position);
+        Ok(position)
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    f64::consts::{FRAC_PI_2, SQRT_2, TAU},
+    io::{Seek, SeekFrom},
+};
+
+use crate::{Amplitude, Sample, Stereo};
+
+/// The delay every voice sways around, in seconds.
+const BASE_DELAY: f64 = 0.02;
+
+/// Adds `voices` copies of the stream, each from a delay line swaying by `depth` seconds `rate`
+/// times per second, out of step with each other.
+///
+/// The voices are spread from left to right, so a mono stream comes out as stereo. Once the input
+/// ends the chorus keeps going until its longest delay has played out.
+#[derive(Clone)]
+pub struct Chorus<T> {
+    pub generator: T,
+    pub voices: usize,
+    pub rate: f64,
+    pub depth: f64,
+    /// The level of the voices, the dry stream is kept as is
+    pub mix: f64,
+    pub sample_rate: u64,
+    buffer: Vec<f64>,
+    position: usize,
+    phase: u64,
+    ended: bool,
+    tail: usize,
+}
+
+impl<T> Chorus<T> {
+    pub fn new(
+        generator: T,
+        voices: usize,
+        rate: f64,
+        depth: f64,
+        mix: f64,
+        sample_rate: u64,
+    ) -> Self {
+        let depth = depth.clamp(0.0, BASE_DELAY);
+        let length = ((BASE_DELAY + depth) * sample_rate as f64).ceil() as usize + 2;
+        Chorus {
+            generator,
+            voices: voices.max(1),
+            rate,
+            depth,
+            mix,
+            sample_rate,
+            buffer: vec![0.0; length],
+            position: 0,
+            phase: 0,
+            ended: false,
+            tail: 0,
+        }
+    }
+
+    /// The value `delay` samples ago, between samples if needed.
+    fn delayed(&self, delay: f64) -> f64 {
+        let length = self.buffer.len();
+        let whole = (delay.floor() as usize).min(length - 2);
+        let fraction = delay - whole as f64;
+        let newer = self.buffer[(self.position + length - whole) % length];
+        let older = self.buffer[(self.position + length - whole - 1) % length];
+        newer + (older - newer) * fraction
+    }
+
+    /// The left and right output for `input` at sample `phase`.
+    fn process(&mut self, input: f64, phase: u64) -> Stereo {
+        self.buffer[self.position] = input;
+
+        let time = phase as f64 / self.sample_rate as f64;
+        let mut left = 0.0;
+        let mut right = 0.0;
+        for voice in 0..self.voices {
+            let offset = voice as f64 / self.voices as f64;
+            let delay = BASE_DELAY + self.depth * (TAU * (self.rate * time + offset)).sin();
+            let value = self.delayed(delay * self.sample_rate as f64);
+            // Equal power panning, from hard left to hard right.
+            let pan = if self.voices == 1 {
+                0.5
+            } else {
+                voice as f64 / (self.voices - 1) as f64
+            };
+            left += value * (pan * FRAC_PI_2).cos();
+            right += value * (pan * FRAC_PI_2).sin();
+        }
+        let gain = self.mix * SQRT_2 / self.voices as f64;
+
+        self.position = (self.position + 1) % self.buffer.len();
+        Stereo {
+            left: input + left * gain,
+            right: input + right * gain,
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Chorus<T> {
+    type Item = Sample<Stereo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.ended {
+            if let Some(sample) = self.generator.next() {
+                self.phase = sample.phase + 1;
+                return Some(Sample {
+                    data: self.process(*sample, sample.phase),
+                    phase: sample.phase,
+                });
+            }
+            self.ended = true;
+            // The voices read up to a whole buffer back.
+            self.tail = self.buffer.len() - 1;
+        }
+
+        if self.tail == 0 {
+            return None;
+        }
+        self.tail -= 1;
+        self.phase += 1;
+        Some(Sample {
+            data: self.process(0.0, self.phase - 1),
+            phase: self.phase - 1,
+        })
+    }
+}
+
+/// Seeking empties the delay line.
+impl<T: Seek> Seek for Chorus<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.buffer.fill(0.0);
+        self.ended = false;
+        self.tail = 0;
+        self.generator.seek(pos)
+    }
+}
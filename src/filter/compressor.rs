@@ -0,0 +1,120 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample};
+
+/// Quieter than this counts as silence when converting to dB.
+const SILENCE: f64 = -200.0;
+
+pub(crate) fn to_decibels(amplitude: f64) -> f64 {
+    if amplitude > 0.0 {
+        20.0 * amplitude.log10()
+    } else {
+        SILENCE
+    }
+}
+
+pub(crate) fn from_decibels(decibels: f64) -> f64 {
+    10f64.powf(decibels / 20.0)
+}
+
+/// How much of the previous value a one pole smoother keeps every sample, so it gets about 63%
+/// of the way to a new value in `time` seconds.
+pub(crate) fn smoothing(time: f64, sample_rate: u64) -> f64 {
+    if time > 0.0 {
+        (-1.0 / (time * sample_rate as f64)).exp()
+    } else {
+        0.0
+    }
+}
+
+/// Turns down everything louder than `threshold` dB, so it only gets `1 / ratio` dB louder for
+/// every dB it goes over.
+///
+/// Gain reduction starts over `attack` seconds and stops over `release` seconds. Within `knee`
+/// dB around the threshold the ratio grows gradually.
+#[derive(Clone)]
+pub struct Compressor<T> {
+    pub generator: T,
+    pub threshold: f64,
+    pub ratio: f64,
+    pub attack: f64,
+    pub release: f64,
+    pub knee: f64,
+    /// Gain in dB applied after compressing, to make up for the lost level
+    pub makeup_gain: f64,
+    pub sample_rate: u64,
+    reduction: f64,
+}
+
+impl<T> Compressor<T> {
+    pub fn new(
+        generator: T,
+        threshold: f64,
+        ratio: f64,
+        attack: f64,
+        release: f64,
+        knee: f64,
+        sample_rate: u64,
+    ) -> Self {
+        Compressor {
+            generator,
+            threshold,
+            ratio,
+            attack,
+            release,
+            knee,
+            makeup_gain: 0.0,
+            sample_rate,
+            reduction: 0.0,
+        }
+    }
+
+    /// Adds `makeup_gain` dB after compressing.
+    pub fn with_makeup_gain(self, makeup_gain: f64) -> Self {
+        Compressor {
+            makeup_gain,
+            ..self
+        }
+    }
+
+    /// The gain reduction in dB for a level of `level` dB, before smoothing.
+    pub fn gain_reduction(&self, level: f64) -> f64 {
+        let over = level - self.threshold;
+        let slope = 1.0 / self.ratio.max(1.0) - 1.0;
+        let knee = self.knee.max(0.0);
+        if 2.0 * over < -knee {
+            0.0
+        } else if knee > 0.0 && 2.0 * over.abs() <= knee {
+            slope * (over + knee / 2.0).powi(2) / (2.0 * knee)
+        } else {
+            slope * over
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Compressor<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let target = self.gain_reduction(to_decibels(sample.abs()));
+            // Reductions are negative, so a smaller target means more reduction.
+            let smoothing = if target < self.reduction {
+                smoothing(self.attack, self.sample_rate)
+            } else {
+                smoothing(self.release, self.sample_rate)
+            };
+            self.reduction = target + (self.reduction - target) * smoothing;
+            Sample {
+                data: Amplitude(*sample * from_decibels(self.reduction + self.makeup_gain)),
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek> Seek for Compressor<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
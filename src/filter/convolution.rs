@@ -0,0 +1,301 @@
+use std::{
+    collections::VecDeque,
+    f64::consts::TAU,
+    io::{Error, ErrorKind, Seek, SeekFrom},
+    ops::{Add, Mul, Sub},
+    path::Path,
+};
+
+use crate::{wav, Amplitude, Sample};
+
+/// The largest block the impulse response is split into, longer blocks need fewer partitions
+/// but delay the first output more.
+const MAX_BLOCK_SIZE: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Complex {
+    real: f64,
+    imaginary: f64,
+}
+
+impl Complex {
+    fn from_angle(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Complex {
+            real: cos,
+            imaginary: sin,
+        }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex {
+            real: self.real + other.real,
+            imaginary: self.imaginary + other.imaginary,
+        }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex {
+            real: self.real - other.real,
+            imaginary: self.imaginary - other.imaginary,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex {
+            real: self.real * other.real - self.imaginary * other.imaginary,
+            imaginary: self.real * other.imaginary + self.imaginary * other.real,
+        }
+    }
+}
+
+/// In place radix-2 FFT, `values.len()` has to be a power of two. The inverse is scaled by
+/// `1 / len`.
+fn fft(values: &mut [Complex], inverse: bool) {
+    let len = values.len();
+    let mut j = 0;
+    for i in 1..len {
+        let mut bit = len >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut size = 2;
+    while size <= len {
+        let step = Complex::from_angle(sign * TAU / size as f64);
+        for start in (0..len).step_by(size) {
+            let mut twiddle = Complex {
+                real: 1.0,
+                imaginary: 0.0,
+            };
+            for k in 0..size / 2 {
+                let even = values[start + k];
+                let odd = values[start + k + size / 2] * twiddle;
+                values[start + k] = even + odd;
+                values[start + k + size / 2] = even - odd;
+                twiddle = twiddle * step;
+            }
+        }
+        size <<= 1;
+    }
+
+    if inverse {
+        for value in values.iter_mut() {
+            value.real /= len as f64;
+            value.imaginary /= len as f64;
+        }
+    }
+}
+
+/// An impulse response split into equal blocks, each already transformed for
+/// [`Convolution`].
+#[derive(Clone, Debug)]
+pub struct ImpulseResponse {
+    len: usize,
+    block_size: usize,
+    partitions: Vec<Vec<Complex>>,
+}
+
+impl ImpulseResponse {
+    pub fn new(samples: &[f64]) -> Self {
+        let block_size = samples.len().next_power_of_two().clamp(1, MAX_BLOCK_SIZE);
+        let partitions = samples
+            .chunks(block_size)
+            .map(|block| {
+                let mut spectrum = vec![Complex::default(); 2 * block_size];
+                for (value, sample) in spectrum.iter_mut().zip(block) {
+                    value.real = *sample;
+                }
+                fft(&mut spectrum, false);
+                spectrum
+            })
+            .collect();
+        ImpulseResponse {
+            len: samples.len(),
+            block_size,
+            partitions,
+        }
+    }
+
+    /// Loads the first channel of a WAV file, which has to be at `sample_rate`.
+    pub fn load<P: AsRef<Path>>(path: P, sample_rate: u64) -> std::io::Result<Self> {
+        let wav = wav::load(path)?;
+        if wav.spec.sample_rate as u64 != sample_rate {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "impulse response is at {}Hz, not {sample_rate}Hz",
+                    wav.spec.sample_rate
+                ),
+            ));
+        }
+        Ok(ImpulseResponse::new(&wav.channel(0)))
+    }
+
+    /// Length in samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl From<Vec<f64>> for ImpulseResponse {
+    fn from(samples: Vec<f64>) -> Self {
+        ImpulseResponse::new(&samples)
+    }
+}
+
+impl From<&[f64]> for ImpulseResponse {
+    fn from(samples: &[f64]) -> Self {
+        ImpulseResponse::new(samples)
+    }
+}
+
+/// Convolves the stream with an impulse response, using uniformly partitioned overlap-save FFT
+/// convolution.
+///
+/// Reads a block of the stream ahead, so the output isn't delayed. Once the stream ends it keeps
+/// going for the length of the impulse response. An empty impulse response passes the stream
+/// through unchanged.
+#[derive(Clone)]
+pub struct Convolution<T> {
+    pub generator: T,
+    pub impulse_response: ImpulseResponse,
+    /// The previous and the current block of input
+    history: Vec<f64>,
+    /// Transforms of the last blocks of input, newest first
+    spectra: VecDeque<Vec<Complex>>,
+    output: VecDeque<Sample<Amplitude>>,
+    phase: u64,
+    ended: bool,
+    tail: usize,
+}
+
+impl<T> Convolution<T> {
+    pub fn new<I: Into<ImpulseResponse>>(generator: T, impulse_response: I) -> Self {
+        let impulse_response = impulse_response.into();
+        Convolution {
+            generator,
+            history: vec![0.0; 2 * impulse_response.block_size],
+            spectra: VecDeque::new(),
+            output: VecDeque::new(),
+            phase: 0,
+            ended: false,
+            tail: 0,
+            impulse_response,
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Convolution<T> {
+    /// Convolves the next block of input, queueing up to a block of output.
+    fn process_block(&mut self) {
+        let block_size = self.impulse_response.block_size;
+        self.history.copy_within(block_size.., 0);
+        let mut phases = Vec::with_capacity(block_size);
+        for value in &mut self.history[block_size..] {
+            *value = 0.0;
+            if !self.ended {
+                match self.generator.next() {
+                    Some(sample) => {
+                        *value = *sample;
+                        phases.push(sample.phase);
+                        self.phase = sample.phase + 1;
+                        continue;
+                    }
+                    None => {
+                        self.ended = true;
+                        self.tail = self.impulse_response.len.saturating_sub(1);
+                    }
+                }
+            }
+            if self.tail > 0 {
+                self.tail -= 1;
+                phases.push(self.phase);
+                self.phase += 1;
+            }
+        }
+        if phases.is_empty() {
+            return;
+        }
+
+        let mut spectrum: Vec<Complex> = self
+            .history
+            .iter()
+            .map(|value| Complex {
+                real: *value,
+                imaginary: 0.0,
+            })
+            .collect();
+        fft(&mut spectrum, false);
+        self.spectra.push_front(spectrum);
+        self.spectra
+            .truncate(self.impulse_response.partitions.len());
+
+        let mut sum = vec![Complex::default(); 2 * block_size];
+        for (input, partition) in self.spectra.iter().zip(&self.impulse_response.partitions) {
+            for ((sum, input), partition) in sum.iter_mut().zip(input).zip(partition) {
+                *sum = *sum + *input * *partition;
+            }
+        }
+        fft(&mut sum, true);
+
+        self.output.extend(
+            sum[block_size..]
+                .iter()
+                .zip(phases)
+                .map(|(value, phase)| Sample {
+                    data: Amplitude(value.real),
+                    phase,
+                }),
+        );
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Convolution<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.impulse_response.is_empty() {
+            return self.generator.next();
+        }
+        if self.output.is_empty() {
+            self.process_block();
+        }
+        self.output.pop_front()
+    }
+}
+
+/// Seeking empties the buffers.
+impl<T: Seek> Seek for Convolution<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.history.fill(0.0);
+        self.spectra.clear();
+        self.output.clear();
+        self.ended = false;
+        self.tail = 0;
+        self.generator.seek(pos)
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample};
+
+/// Echoes quieter than this end the tail of a [`Delay`], about -80dB.
+pub const DEFAULT_TAIL_THRESHOLD: f64 = 0.0001;
+
+/// The length in seconds of `beats` beats at `tempo` beats per minute, for echoes in time with
+/// music, e.g. `beats(120.0, 0.75)` for a dotted eighth at 120bpm.
+pub fn beats(tempo: f64, beats: f64) -> f64 {
+    beats * 60.0 / tempo
+}
+
+/// Echo from a ring buffer, each echo `feedback` times as loud as the one before it.
+///
+/// Once the input ends the echoes keep going until they are all quieter than `tail_threshold`.
+#[derive(Clone)]
+pub struct Delay<T> {
+    pub generator: T,
+    /// Time between echoes in seconds
+    pub time: f64,
+    pub feedback: f64,
+    /// The level of the echoes, the dry stream is kept as is
+    pub mix: f64,
+    pub sample_rate: u64,
+    /// Cutoff in Hz of a low-pass in the feedback path, so each echo is duller than the last
+    pub damping: Option<f64>,
+    pub tail_threshold: f64,
+    buffer: Vec<f64>,
+    position: usize,
+    damped: f64,
+    phase: u64,
+    ended: bool,
+    lap_peak: f64,
+    lap_elapsed: usize,
+}
+
+impl<T> Delay<T> {
+    pub fn new(generator: T, time: f64, feedback: f64, mix: f64, sample_rate: u64) -> Self {
+        Delay {
+            generator,
+            time,
+            feedback,
+            mix,
+            sample_rate,
+            damping: None,
+            tail_threshold: DEFAULT_TAIL_THRESHOLD,
+            buffer: vec![0.0; ((time * sample_rate as f64).round() as usize).max(1)],
+            position: 0,
+            damped: 0.0,
+            phase: 0,
+            ended: false,
+            lap_peak: 0.0,
+            lap_elapsed: 0,
+        }
+    }
+
+    /// Low-passes the feedback at `cutoff` Hz.
+    pub fn damped(self, cutoff: f64) -> Self {
+        Delay {
+            damping: Some(cutoff),
+            ..self
+        }
+    }
+
+    pub fn with_tail_threshold(self, tail_threshold: f64) -> Self {
+        Delay {
+            tail_threshold,
+            ..self
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let delayed = self.buffer[self.position];
+        let fed_back = match self.damping {
+            Some(cutoff) => {
+                let omega = std::f64::consts::TAU * cutoff;
+                self.damped += omega / (self.sample_rate as f64 + omega) * (delayed - self.damped);
+                self.damped
+            }
+            None => delayed,
+        };
+        self.buffer[self.position] = input + fed_back * self.feedback.clamp(-0.999, 0.999);
+        self.position = (self.position + 1) % self.buffer.len();
+        input + delayed * self.mix
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Delay<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.ended {
+            if let Some(sample) = self.generator.next() {
+                self.phase = sample.phase + 1;
+                return Some(Sample {
+                    data: Amplitude(self.process(*sample)),
+                    ..sample
+                });
+            }
+            self.ended = true;
+        }
+
+        // Stop once a whole lap of the buffer has been quiet.
+        if self.lap_elapsed == self.buffer.len() {
+            if self.lap_peak < self.tail_threshold {
+                return None;
+            }
+            self.lap_peak = 0.0;
+            self.lap_elapsed = 0;
+        }
+        self.lap_peak = self.lap_peak.max(self.buffer[self.position].abs());
+        self.lap_elapsed += 1;
+        self.phase += 1;
+        Some(Sample {
+            data: Amplitude(self.process(0.0)),
+            phase: self.phase - 1,
+        })
+    }
+}
+
+/// Seeking empties the buffer.
+impl<T: Seek> Seek for Delay<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.buffer.fill(0.0);
+        self.damped = 0.0;
+        self.ended = false;
+        self.lap_peak = 0.0;
+        self.lap_elapsed = 0;
+        self.generator.seek(pos)
+    }
+}
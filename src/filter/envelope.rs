@@ -7,6 +7,7 @@ pub struct Envelope<T> {
     pub generator: T,
     pub attack_time: f64,
     pub sustain_time: f64,
+    /// Level at the start of the sustain, it falls linearly to 1 by the start of the decay
     pub sustain_punch: f64,
     pub decay_time: f64,
     pub sample_rate: u64,
@@ -22,7 +23,9 @@ impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Envelope<T> {
             } else if (phase as f64)
                 < (self.attack_time + self.sustain_time) * self.sample_rate as f64
             {
-                Some(*data * self.sustain_punch)
+                let sustained =
+                    (phase as f64 / self.sample_rate as f64 - self.attack_time) / self.sustain_time;
+                Some(*data * (self.sustain_punch + (1.0 - self.sustain_punch) * sustained))
             } else if (phase as f64)
                 < (self.attack_time + self.sustain_time + self.decay_time) * self.sample_rate as f64
             {
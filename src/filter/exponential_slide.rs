@@ -0,0 +1,35 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Frequency, Sample};
+
+/// Multiplies the frequency by `e^slide` every second, `slide` itself changes by `slide_delta`
+/// every second.
+#[derive(Clone, Copy)]
+pub struct ExponentialSlide<T> {
+    pub generator: T,
+    pub slide: f64,
+    pub slide_delta: f64,
+    pub sample_rate: u64,
+}
+
+impl<T: Seek> Seek for ExponentialSlide<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for ExponentialSlide<T> {
+    type Item = Sample<Frequency>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let time = sample.phase as f64 / self.sample_rate as f64;
+            Sample {
+                data: Frequency(
+                    *sample * (self.slide * time + self.slide_delta * time * time / 2.0).exp(),
+                ),
+                ..sample
+            }
+        })
+    }
+}
@@ -1,4 +1,7 @@
-use std::{io::{Seek, SeekFrom}, ops::Deref};
+use std::{
+    io::{Seek, SeekFrom},
+    ops::Deref,
+};
 
 use crate::Sample;
 
@@ -20,27 +23,24 @@ where
     type Item = Sample<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.generator
-            .next()
-            .map(|sample| {
-                if sample.phase as f64 / self.sample_rate as f64 > self.offset {
-                    let mut temp = self.generator.clone();
-                    temp.seek(SeekFrom::Current(
-                        (self.sample_rate as f64
-                            * self.sweep
-                            * (sample.phase as f64 / self.sample_rate as f64 - self.offset).sin())
-                        .round() as i64,
-                    ))
-                    .unwrap();
-                    temp.next().map(|sample1| Sample {
-                        data: T::from(*sample + *sample1),
-                        ..sample
-                    })
-                } else {
-                    Some(sample)
-                }
-            })
-            .flatten()
+        self.generator.next().and_then(|sample| {
+            if sample.phase as f64 / self.sample_rate as f64 > self.offset {
+                let mut temp = self.generator.clone();
+                temp.seek(SeekFrom::Current(
+                    (self.sample_rate as f64
+                        * self.sweep
+                        * (sample.phase as f64 / self.sample_rate as f64 - self.offset).sin())
+                    .round() as i64,
+                ))
+                .unwrap();
+                temp.next().map(|sample1| Sample {
+                    data: T::from(*sample + *sample1),
+                    ..sample
+                })
+            } else {
+                Some(sample)
+            }
+        })
     }
 }
 
@@ -1,25 +1,28 @@
 use std::io::{Seek, SeekFrom};
 
-use crate::{Amplitude, Sample};
+use crate::{modulation::Modulation, Amplitude, Sample};
 
+/// One pole RC high-pass filter, the cutoff is either a fixed `f64` or any [`Modulation`].
 #[derive(Clone)]
-pub struct Highpass<T> {
+pub struct Highpass<T, C = f64> {
     pub generator: T,
-    pub cutoff: f64,
-    pub smoothing_factor: f64,
+    /// Cutoff frequency in Hz
+    pub cutoff: C,
+    pub sample_rate: u64,
     prev_sample: Sample<Amplitude>,
     prev_output_sample: Sample<Amplitude>,
 }
 
-impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Highpass<T> {
+impl<T: Iterator<Item = Sample<Amplitude>>, C: Modulation> Iterator for Highpass<T, C> {
     type Item = Sample<Amplitude>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.generator.next().map(|sample| {
+            let smoothing_factor = smoothing_factor(self.cutoff.next_value(), self.sample_rate);
             self.prev_output_sample = Sample {
                 data: Amplitude(
-                    *self.prev_output_sample * self.smoothing_factor
-                        + self.smoothing_factor * (*sample - *self.prev_sample),
+                    *self.prev_output_sample * smoothing_factor
+                        + smoothing_factor * (*sample - *self.prev_sample),
                 ),
                 ..sample
             };
@@ -29,18 +32,19 @@ impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Highpass<T> {
     }
 }
 
-impl<T: Seek> Seek for Highpass<T> {
+impl<T: Seek, C: Modulation> Seek for Highpass<T, C> {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
-        self.generator.seek(pos)
+        let position = self.generator.seek(pos)?;
+        self.cutoff.seek_to(position);
+        Ok(position)
     }
 }
 
-impl<T: Iterator<Item = Sample<Amplitude>>> Highpass<T> {
-    pub fn new(generator: T, cutoff: f64, sample_rate: u64) -> Self {
+impl<T, C> Highpass<T, C> {
+    pub fn new(generator: T, cutoff: C, sample_rate: u64) -> Self {
         Highpass {
             cutoff,
-            smoothing_factor: sample_rate as f64
-                / (sample_rate as f64 + 2.0 * std::f64::consts::PI * cutoff),
+            sample_rate,
             prev_sample: Sample {
                 data: Amplitude(0.0),
                 phase: 0,
@@ -53,3 +57,18 @@ impl<T: Iterator<Item = Sample<Amplitude>>> Highpass<T> {
         }
     }
 }
+
+impl<T> Highpass<T> {
+    /// The gain of the filter for a sine wave at `frequency` Hz, `1.0` passes it unchanged.
+    pub fn magnitude_response(&self, frequency: f64) -> f64 {
+        let omega = std::f64::consts::TAU * frequency / self.sample_rate as f64;
+        let pole = smoothing_factor(self.cutoff, self.sample_rate);
+        pole * (2.0 - 2.0 * omega.cos()).sqrt()
+            / (1.0 - 2.0 * pole * omega.cos() + pole * pole).sqrt()
+    }
+}
+
+/// How much of the previous output is kept every sample, `RC / (RC + dt)`.
+fn smoothing_factor(cutoff: f64, sample_rate: u64) -> f64 {
+    sample_rate as f64 / (sample_rate as f64 + std::f64::consts::TAU * cutoff.max(0.0))
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::VecDeque,
+    io::{Seek, SeekFrom},
+};
+
+use crate::{Amplitude, Sample};
+
+use super::compressor::{from_decibels, smoothing};
+
+/// Brickwall limiter, no sample gets louder than `ceiling` dB.
+///
+/// Looks `lookahead` seconds ahead so the gain is already down when a peak arrives, instead of
+/// clipping it. The stream is read that far ahead but not delayed. The gain comes back up over
+/// `release` seconds.
+#[derive(Clone)]
+pub struct Limiter<T> {
+    pub generator: T,
+    pub ceiling: f64,
+    pub release: f64,
+    pub sample_rate: u64,
+    lookahead: usize,
+    /// Samples waiting for their gain, `None` for the silence after the stream ends
+    delay: VecDeque<Option<Sample<Amplitude>>>,
+    /// Indices and gains that could still be the smallest of the lookahead window, ascending
+    minimums: VecDeque<(u64, f64)>,
+    /// The last `lookahead + 1` gains after release, and their sum
+    gains: VecDeque<f64>,
+    gain_sum: f64,
+    gain: f64,
+    index: u64,
+    ended: bool,
+}
+
+impl<T> Limiter<T> {
+    pub fn new(generator: T, ceiling: f64, lookahead: f64, release: f64, sample_rate: u64) -> Self {
+        let lookahead = (lookahead * sample_rate as f64).round().max(0.0) as usize;
+        Limiter {
+            generator,
+            ceiling,
+            release,
+            sample_rate,
+            lookahead,
+            delay: VecDeque::with_capacity(lookahead + 1),
+            minimums: VecDeque::new(),
+            gains: VecDeque::with_capacity(lookahead + 1),
+            gain_sum: 0.0,
+            gain: 1.0,
+            index: 0,
+            ended: false,
+        }
+    }
+
+    /// Queues a sample, and returns the one `lookahead` samples before it once it knows its gain.
+    fn push(&mut self, sample: Option<Sample<Amplitude>>) -> Option<Option<Sample<Amplitude>>> {
+        let ceiling = from_decibels(self.ceiling);
+        let target = match sample {
+            Some(sample) if sample.abs() > ceiling => ceiling / sample.abs(),
+            _ => 1.0,
+        };
+
+        while self
+            .minimums
+            .back()
+            .is_some_and(|(_, gain)| *gain >= target)
+        {
+            self.minimums.pop_back();
+        }
+        self.minimums.push_back((self.index, target));
+        while self
+            .minimums
+            .front()
+            .is_some_and(|(index, _)| index + (self.lookahead as u64) < self.index)
+        {
+            self.minimums.pop_front();
+        }
+        self.index += 1;
+
+        // Drop straight to the smallest gain ahead, come back up slowly.
+        let minimum = self.minimums.front().map_or(1.0, |(_, gain)| *gain);
+        self.gain = if minimum < self.gain {
+            minimum
+        } else {
+            minimum + (self.gain - minimum) * smoothing(self.release, self.sample_rate)
+        };
+
+        // Averaging over the lookahead turns the drops into ramps, which still reach the
+        // smallest gain by the time its sample comes out.
+        self.gains.push_back(self.gain);
+        self.gain_sum += self.gain;
+        if self.gains.len() > self.lookahead + 1 {
+            self.gain_sum -= self.gains.pop_front().unwrap_or_default();
+        }
+
+        self.delay.push_back(sample);
+        if self.delay.len() > self.lookahead {
+            let gain = (self.gain_sum / self.gains.len() as f64).min(1.0);
+            self.delay.pop_front().map(|sample| {
+                sample.map(|sample| Sample {
+                    data: Amplitude((*sample * gain).clamp(-ceiling, ceiling)),
+                    ..sample
+                })
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Limiter<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let sample = if self.ended {
+                None
+            } else {
+                self.generator.next()
+            };
+            if sample.is_none() {
+                self.ended = true;
+                if self.delay.iter().all(Option::is_none) {
+                    return None;
+                }
+            }
+            if let Some(Some(output)) = self.push(sample) {
+                return Some(output);
+            }
+        }
+    }
+}
+
+/// Seeking empties the lookahead.
+impl<T: Seek> Seek for Limiter<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.delay.clear();
+        self.minimums.clear();
+        self.gains.clear();
+        self.gain_sum = 0.0;
+        self.gain = 1.0;
+        self.ended = false;
+        self.generator.seek(pos)
+    }
+}
@@ -2,12 +2,14 @@ use std::io::{Seek, SeekFrom};
 
 use crate::{Amplitude, Sample};
 
+/// One pole RC low-pass filter.
 #[derive(Clone)]
 pub struct Lowpass<T> {
     pub generator: T,
     pub cutoff: f64,
+    pub sample_rate: u64,
     pub smoothing_factor: f64,
-    prev_sample: Sample<Amplitude>,
+    prev_output: f64,
 }
 
 impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Lowpass<T> {
@@ -15,15 +17,11 @@ impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Lowpass<T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.generator.next().map(|sample| {
-            let output = Sample {
-                data: Amplitude(
-                    *sample * self.smoothing_factor
-                        + *self.prev_sample * (1.0 - self.smoothing_factor),
-                ),
+            self.prev_output += self.smoothing_factor * (*sample - self.prev_output);
+            Sample {
+                data: Amplitude(self.prev_output),
                 ..sample
-            };
-            self.prev_sample = sample;
-            output
+            }
         })
     }
 }
@@ -34,17 +32,22 @@ impl<T: Seek> Seek for Lowpass<T> {
     }
 }
 
-impl<T: Iterator<Item = Sample<Amplitude>>> Lowpass<T> {
+impl<T> Lowpass<T> {
     pub fn new(generator: T, cutoff: f64, sample_rate: u64) -> Self {
+        let omega = std::f64::consts::TAU * cutoff;
         Lowpass {
             cutoff,
-            smoothing_factor: 1.0
-                / (1.0 + 2.0 * std::f64::consts::PI * cutoff * sample_rate as f64),
-            prev_sample: Sample {
-                data: Amplitude(0.0),
-                phase: 0,
-            },
+            sample_rate,
+            smoothing_factor: omega / (sample_rate as f64 + omega),
+            prev_output: 0.0,
             generator,
         }
     }
+
+    /// The gain of the filter for a sine wave at `frequency` Hz, `1.0` passes it unchanged.
+    pub fn magnitude_response(&self, frequency: f64) -> f64 {
+        let omega = std::f64::consts::TAU * frequency / self.sample_rate as f64;
+        let pole = 1.0 - self.smoothing_factor;
+        self.smoothing_factor / (1.0 - 2.0 * pole * omega.cos() + pole * pole).sqrt()
+    }
 }
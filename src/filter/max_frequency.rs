@@ -0,0 +1,26 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Frequency, Sample};
+
+#[derive(Clone, Copy)]
+pub struct MaxFrequency<T> {
+    pub generator: T,
+    pub max_frequency: f64,
+}
+
+impl<T: Seek> Seek for MaxFrequency<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for MaxFrequency<T> {
+    type Item = Sample<Frequency>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| Sample {
+            data: Frequency(sample.min(self.max_frequency)),
+            ..sample
+        })
+    }
+}
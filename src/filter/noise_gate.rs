@@ -0,0 +1,65 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample};
+
+use super::compressor::{from_decibels, smoothing};
+
+/// Silences the stream while it is quieter than `threshold` dB, e.g. to cut off a long quiet
+/// tail.
+///
+/// Opens over `attack` seconds once the stream gets louder than the threshold and closes over
+/// `release` seconds once it drops back below it.
+#[derive(Clone)]
+pub struct NoiseGate<T> {
+    pub generator: T,
+    pub threshold: f64,
+    pub attack: f64,
+    pub release: f64,
+    pub sample_rate: u64,
+    level: f64,
+    gain: f64,
+}
+
+impl<T> NoiseGate<T> {
+    pub fn new(generator: T, threshold: f64, attack: f64, release: f64, sample_rate: u64) -> Self {
+        NoiseGate {
+            generator,
+            threshold,
+            attack,
+            release,
+            sample_rate,
+            level: 0.0,
+            gain: 0.0,
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for NoiseGate<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            // Follow peaks at once and let the level fall over the release time, so the gate
+            // doesn't chatter on every zero crossing.
+            self.level = sample
+                .abs()
+                .max(self.level * smoothing(self.release, self.sample_rate));
+            let (target, time) = if self.level > from_decibels(self.threshold) {
+                (1.0, self.attack)
+            } else {
+                (0.0, self.release)
+            };
+            self.gain = target + (self.gain - target) * smoothing(time, self.sample_rate);
+            Sample {
+                data: Amplitude(*sample * self.gain),
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek> Seek for NoiseGate<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample};
+
+/// Adds a delayed copy of the stream to itself, with the delay moving over time, like sfxr's
+/// phaser.
+///
+/// The delay starts at `delay` seconds and changes by `sweep` seconds every second. Like sfxr it
+/// is mirrored at zero and stops at `max_delay`.
+#[derive(Clone)]
+pub struct Phaser<T> {
+    pub generator: T,
+    pub delay: f64,
+    pub sweep: f64,
+    /// How much of the delayed stream is fed back into the delay line
+    pub feedback: f64,
+    /// The level of the delayed stream, sfxr uses `1.0`
+    pub mix: f64,
+    pub sample_rate: u64,
+    max_delay: f64,
+    buffer: Vec<f64>,
+    position: usize,
+}
+
+impl<T> Phaser<T> {
+    pub fn new(
+        generator: T,
+        delay: f64,
+        sweep: f64,
+        max_delay: f64,
+        feedback: f64,
+        mix: f64,
+        sample_rate: u64,
+    ) -> Self {
+        let max_delay = max_delay.max(0.0);
+        let length = (max_delay * sample_rate as f64).ceil() as usize + 2;
+        Phaser {
+            generator,
+            delay,
+            sweep,
+            feedback,
+            mix,
+            sample_rate,
+            max_delay,
+            buffer: vec![0.0; length],
+            position: 0,
+        }
+    }
+
+    /// The delay in samples at sample `phase`.
+    fn delay(&self, phase: u64) -> f64 {
+        let time = phase as f64 / self.sample_rate as f64;
+        (self.delay + self.sweep * time).abs().min(self.max_delay) * self.sample_rate as f64
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Phaser<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let length = self.buffer.len();
+            self.buffer[self.position] = *sample;
+
+            let delay = self.delay(sample.phase);
+            let whole = delay.floor() as usize;
+            let fraction = delay - whole as f64;
+            let newer = self.buffer[(self.position + length - whole) % length];
+            let older = self.buffer[(self.position + length - whole - 1) % length];
+            let delayed = newer + (older - newer) * fraction;
+
+            self.buffer[self.position] += delayed * self.feedback.clamp(-0.99, 0.99);
+            self.position = (self.position + 1) % length;
+            Sample {
+                data: Amplitude(*sample + delayed * self.mix),
+                ..sample
+            }
+        })
+    }
+}
+
+/// Seeking empties the delay line.
+impl<T: Seek> Seek for Phaser<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.buffer.fill(0.0);
+        self.generator.seek(pos)
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Frequency, Sample};
+
+/// Rewinds the generator every `period` samples while the phase of the stream keeps counting up,
+/// so slides and arpeggios restart without restarting anything that comes after.
+#[derive(Clone, Copy)]
+pub struct Repeat<T> {
+    pub generator: T,
+    pub period: u64,
+    cycle: u64,
+    elapsed: u64,
+}
+
+impl<T> Repeat<T> {
+    pub fn new(generator: T, period: u64) -> Self {
+        Repeat {
+            generator,
+            period,
+            cycle: 0,
+            elapsed: 0,
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Iterator for Repeat<T> {
+    type Item = Sample<Frequency>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.period > 0 && self.elapsed >= self.period {
+            self.generator.rewind().ok()?;
+            self.cycle += 1;
+            self.elapsed = 0;
+        }
+        self.elapsed += 1;
+        self.generator.next().map(|sample| Sample {
+            phase: self.cycle * self.period + sample.phase,
+            ..sample
+        })
+    }
+}
+
+impl<T: Seek> Seek for Repeat<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = match pos {
+            SeekFrom::Start(pos) => pos,
+            SeekFrom::End(_) => return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput)),
+            SeekFrom::Current(pos) => (self.cycle * self.period + self.elapsed)
+                .checked_add_signed(pos)
+                .ok_or(std::io::Error::from(std::io::ErrorKind::InvalidInput))?,
+        };
+        self.cycle = position.checked_div(self.period).unwrap_or(0);
+        self.elapsed = position - self.cycle * self.period;
+        self.generator.seek(SeekFrom::Start(self.elapsed))?;
+        Ok(position)
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{modulation::Modulation, Amplitude, Sample};
+
+use super::biquad::{BiquadKind, Coefficients, DirectForm};
+
+/// A two pole high-pass filter with a resonant peak at the cutoff, both of which can change on
+/// every sample.
+#[derive(Clone, Copy)]
+pub struct ResonantHighpass<T, C = f64, R = f64> {
+    pub generator: T,
+    /// Cutoff frequency in Hz
+    pub cutoff: C,
+    /// The Q factor, `0.707` gives a flat response above the cutoff, higher values a peak at it
+    pub resonance: R,
+    pub sample_rate: u64,
+    state: DirectForm,
+}
+
+impl<T, C, R> ResonantHighpass<T, C, R> {
+    pub fn new(generator: T, cutoff: C, resonance: R, sample_rate: u64) -> Self {
+        ResonantHighpass {
+            generator,
+            cutoff,
+            resonance,
+            sample_rate,
+            state: DirectForm::default(),
+        }
+    }
+}
+
+impl<T, C, R> Iterator for ResonantHighpass<T, C, R>
+where
+    T: Iterator<Item = Sample<Amplitude>>,
+    C: Modulation,
+    R: Modulation,
+{
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let coefficients = Coefficients::design(
+                BiquadKind::Highpass,
+                self.cutoff.next_value(),
+                self.resonance.next_value(),
+                0.0,
+                self.sample_rate,
+            );
+            Sample {
+                data: Amplitude(self.state.process(&coefficients, *sample)),
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek, C: Modulation, R: Modulation> Seek for ResonantHighpass<T, C, R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.generator.seek(pos)?;
+        self.cutoff.seek_to(position);
+        self.resonance.seek_to(position);
+        Ok(position)
+    }
+}
@@ -1,4 +1,7 @@
-use std::{io::{Seek, SeekFrom}, ops::Deref};
+use std::{
+    io::{Seek, SeekFrom},
+    ops::Deref,
+};
 
 use crate::Sample;
 
@@ -34,14 +37,14 @@ where
         }
 
         if self.num_retriggers as usize >= self.retriggers.len()
-            && ((self.phase as f64 * self.rate) as u64 > self.sample_rate * (self.retriggers.len() as u64 + 1))
+            && ((self.phase as f64 * self.rate) as u64
+                > self.sample_rate * (self.retriggers.len() as u64 + 1))
         {
             let mut temp = self.generator.clone();
-            println!("Phase: {}", self.phase);
             temp.rewind().unwrap();
             self.retriggers.push(temp);
         }
-        if samples.len() > 0 {
+        if !samples.is_empty() {
             Some(Sample {
                 data: T::from(samples.into_iter().map(|v| *v).sum::<f64>()),
                 phase: self.phase,
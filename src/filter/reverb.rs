@@ -0,0 +1,234 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample, Stereo};
+
+use super::delay::DEFAULT_TAIL_THRESHOLD;
+
+/// Freeverb's comb filter lengths, in samples at 44100Hz.
+const COMB_TUNINGS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+/// Freeverb's all-pass filter lengths, in samples at 44100Hz.
+const ALLPASS_TUNINGS: [usize; 4] = [556, 441, 341, 225];
+/// How much longer the right channel's filters are than the left's.
+const STEREO_SPREAD: usize = 23;
+const TUNING_SAMPLE_RATE: f64 = 44100.0;
+
+const INPUT_GAIN: f64 = 0.015;
+const WET_SCALE: f64 = 3.0;
+const DRY_SCALE: f64 = 2.0;
+
+/// Low-passed feedback comb filter.
+#[derive(Clone)]
+struct Comb {
+    buffer: Vec<f64>,
+    position: usize,
+    damped: f64,
+}
+
+impl Comb {
+    fn process(&mut self, input: f64, feedback: f64, damping: f64) -> f64 {
+        let output = self.buffer[self.position];
+        self.damped = output * (1.0 - damping) + self.damped * damping;
+        self.buffer[self.position] = input + self.damped * feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// Schroeder all-pass filter.
+#[derive(Clone)]
+struct Allpass {
+    buffer: Vec<f64>,
+    position: usize,
+}
+
+impl Allpass {
+    fn process(&mut self, input: f64) -> f64 {
+        let delayed = self.buffer[self.position];
+        self.buffer[self.position] = input + delayed * 0.5;
+        self.position = (self.position + 1) % self.buffer.len();
+        delayed - input
+    }
+}
+
+/// One channel of the reverb, eight parallel combs followed by four all-passes in series.
+#[derive(Clone)]
+struct Channel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Channel {
+    fn new(spread: usize, sample_rate: u64) -> Self {
+        let length = |tuning: usize| {
+            ((tuning + spread) as f64 * sample_rate as f64 / TUNING_SAMPLE_RATE)
+                .round()
+                .max(1.0) as usize
+        };
+        Channel {
+            combs: COMB_TUNINGS
+                .iter()
+                .map(|tuning| Comb {
+                    buffer: vec![0.0; length(*tuning)],
+                    position: 0,
+                    damped: 0.0,
+                })
+                .collect(),
+            allpasses: ALLPASS_TUNINGS
+                .iter()
+                .map(|tuning| Allpass {
+                    buffer: vec![0.0; length(*tuning)],
+                    position: 0,
+                })
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f64, feedback: f64, damping: f64) -> f64 {
+        let combed = self
+            .combs
+            .iter_mut()
+            .map(|comb| comb.process(input, feedback, damping))
+            .sum();
+        self.allpasses
+            .iter_mut()
+            .fold(combed, |signal, allpass| allpass.process(signal))
+    }
+
+    fn clear(&mut self) {
+        for comb in &mut self.combs {
+            comb.buffer.fill(0.0);
+            comb.damped = 0.0;
+        }
+        for allpass in &mut self.allpasses {
+            allpass.buffer.fill(0.0);
+        }
+    }
+}
+
+/// Freeverb, Jezar's tuning of a Schroeder reverb.
+///
+/// Turns a mono stream into a stereo one, `width` sets how different the two channels are. Once
+/// the input ends the reverb keeps going until it is quieter than `tail_threshold`.
+#[derive(Clone)]
+pub struct Reverb<T> {
+    pub generator: T,
+    /// From `0.0` to `1.0`, bigger rooms ring for longer
+    pub room_size: f64,
+    /// From `0.0` to `1.0`, how fast the high frequencies die out
+    pub damping: f64,
+    /// From `0.0` to `1.0`, how much the channels differ
+    pub width: f64,
+    /// Level of the reverb
+    pub wet: f64,
+    /// Level of the original stream
+    pub dry: f64,
+    pub sample_rate: u64,
+    pub tail_threshold: f64,
+    left: Channel,
+    right: Channel,
+    phase: u64,
+    ended: bool,
+    window_peak: f64,
+    window_elapsed: usize,
+}
+
+impl<T> Reverb<T> {
+    pub fn new(
+        generator: T,
+        room_size: f64,
+        damping: f64,
+        width: f64,
+        wet: f64,
+        dry: f64,
+        sample_rate: u64,
+    ) -> Self {
+        Reverb {
+            generator,
+            room_size,
+            damping,
+            width,
+            wet,
+            dry,
+            sample_rate,
+            tail_threshold: DEFAULT_TAIL_THRESHOLD,
+            left: Channel::new(0, sample_rate),
+            right: Channel::new(STEREO_SPREAD, sample_rate),
+            phase: 0,
+            ended: false,
+            window_peak: 0.0,
+            window_elapsed: 0,
+        }
+    }
+
+    pub fn with_tail_threshold(self, tail_threshold: f64) -> Self {
+        Reverb {
+            tail_threshold,
+            ..self
+        }
+    }
+
+    /// The left and right output for one input sample.
+    fn process(&mut self, input: f64) -> (f64, f64) {
+        let feedback = self.room_size.clamp(0.0, 1.0) * 0.28 + 0.7;
+        let damping = self.damping.clamp(0.0, 1.0) * 0.4;
+        let width = self.width.clamp(0.0, 1.0);
+        let wet_same = self.wet * WET_SCALE * (width / 2.0 + 0.5);
+        let wet_other = self.wet * WET_SCALE * (1.0 - width) / 2.0;
+        let dry = input * self.dry * DRY_SCALE;
+
+        let left = self.left.process(input * INPUT_GAIN, feedback, damping);
+        let right = self.right.process(input * INPUT_GAIN, feedback, damping);
+        (
+            left * wet_same + right * wet_other + dry,
+            right * wet_same + left * wet_other + dry,
+        )
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Reverb<T> {
+    type Item = Sample<Stereo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.ended {
+            if let Some(sample) = self.generator.next() {
+                self.phase = sample.phase + 1;
+                let (left, right) = self.process(*sample);
+                return Some(Sample {
+                    data: Stereo { left, right },
+                    phase: sample.phase,
+                });
+            }
+            self.ended = true;
+        }
+
+        // Stop once the longest comb has gone round without anything audible.
+        let window = self.right.combs.iter().map(|comb| comb.buffer.len()).max();
+        if Some(self.window_elapsed) == window {
+            if self.window_peak < self.tail_threshold {
+                return None;
+            }
+            self.window_peak = 0.0;
+            self.window_elapsed = 0;
+        }
+        let (left, right) = self.process(0.0);
+        self.window_peak = self.window_peak.max(left.abs()).max(right.abs());
+        self.window_elapsed += 1;
+        self.phase += 1;
+        Some(Sample {
+            data: Stereo { left, right },
+            phase: self.phase - 1,
+        })
+    }
+}
+
+/// Seeking empties the reverb.
+impl<T: Seek> Seek for Reverb<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.left.clear();
+        self.right.clear();
+        self.ended = false;
+        self.window_peak = 0.0;
+        self.window_elapsed = 0;
+        self.generator.seek(pos)
+    }
+}
@@ -0,0 +1,36 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample};
+
+/// Multiplies the stream by a carrier stream, giving the sums and differences of their
+/// frequencies instead of either. Ends with whichever stream ends first.
+#[derive(Clone, Copy)]
+pub struct RingMod<T, C> {
+    pub generator: T,
+    pub carrier: C,
+}
+
+impl<T, C> Iterator for RingMod<T, C>
+where
+    T: Iterator<Item = Sample<Amplitude>>,
+    C: Iterator<Item = Sample<Amplitude>>,
+{
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.generator.next()?;
+        let carrier = self.carrier.next()?;
+        Some(Sample {
+            data: Amplitude(*sample * *carrier),
+            ..sample
+        })
+    }
+}
+
+impl<T: Seek, C: Seek> Seek for RingMod<T, C> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.generator.seek(pos)?;
+        self.carrier.seek(SeekFrom::Start(position))?;
+        Ok(position)
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample, Stereo};
+
+use super::compressor::smoothing;
+
+/// How long the running average used to detect DC takes to settle, in seconds.
+const DC_AVERAGING_TIME: f64 = 0.05;
+
+/// How much of the running RMS the average has to make up to count as DC. Narrow pulses have a
+/// large average too, a 10% pulse wave's is 80% of its RMS, but they keep moving around it.
+const DC_SHARE: f64 = 0.9;
+
+/// Something a [`Safeguard`] had to step in for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Correction {
+    /// A NaN or infinite sample, replaced with silence
+    NonFinite,
+    /// A sample past the ceiling, clipped to it
+    Clipped,
+    /// The average of the stream moved past the DC threshold, with the stream barely moving
+    /// around it
+    DcOffset,
+}
+
+/// How many samples a [`Safeguard`] has corrected so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SafeguardReport {
+    pub non_finite: u64,
+    pub clipped: u64,
+    /// Samples during which the average was past the DC threshold, these aren't changed
+    pub dc_offset: u64,
+}
+
+impl SafeguardReport {
+    /// Whether nothing had to be corrected.
+    pub fn is_clean(&self) -> bool {
+        *self == SafeguardReport::default()
+    }
+}
+
+/// Sample data with one or more channels, which a [`Safeguard`] corrects one by one.
+pub trait Channels: Sized {
+    /// Replaces the value of every channel with `map(channel, value)`.
+    fn map_channels<F: FnMut(usize, f64) -> f64>(self, map: F) -> Self;
+}
+
+impl Channels for Amplitude {
+    fn map_channels<F: FnMut(usize, f64) -> f64>(self, mut map: F) -> Self {
+        Amplitude(map(0, self.0))
+    }
+}
+
+impl Channels for Stereo {
+    fn map_channels<F: FnMut(usize, f64) -> f64>(self, mut map: F) -> Self {
+        Stereo {
+            left: map(0, self.left),
+            right: map(1, self.right),
+        }
+    }
+}
+
+/// The last stage before a sink, guarantees every sample is finite and within `-ceiling..=ceiling`.
+///
+/// Also watches for the stream drifting away from zero, which doesn't clip but can damage
+/// speakers. That's when its average is past `dc_threshold` and makes up almost all of its RMS, so
+/// narrow pulse waves, which have a large average but swing around it, aren't counted. Every correction is counted in [`Safeguard::report`] and passed to the
+/// [`on_correction`](Safeguard::on_correction) callback along with the phase of the sample, DC
+/// only once each time it goes past the threshold.
+///
+/// Works on mono and stereo streams, every channel is checked for DC on its own.
+pub struct Safeguard<T> {
+    pub generator: T,
+    pub ceiling: f64,
+    pub dc_threshold: f64,
+    pub sample_rate: u64,
+    report: SafeguardReport,
+    callback: Option<Box<dyn FnMut(Correction, u64)>>,
+    /// The DC detection of every channel seen so far
+    channels: Vec<DcDetector>,
+}
+
+/// Running average and power of one channel.
+#[derive(Clone, Copy, Default)]
+struct DcDetector {
+    average: f64,
+    power: f64,
+    dc: bool,
+}
+
+impl<T> Safeguard<T> {
+    /// A ceiling of `1.0` and a DC threshold of `0.25`.
+    pub fn new(generator: T, sample_rate: u64) -> Self {
+        Safeguard {
+            generator,
+            ceiling: 1.0,
+            dc_threshold: 0.25,
+            sample_rate,
+            report: SafeguardReport::default(),
+            callback: None,
+            channels: Vec::new(),
+        }
+    }
+
+    pub fn with_ceiling(self, ceiling: f64) -> Self {
+        Safeguard { ceiling, ..self }
+    }
+
+    pub fn with_dc_threshold(self, dc_threshold: f64) -> Self {
+        Safeguard {
+            dc_threshold,
+            ..self
+        }
+    }
+
+    /// Calls `callback` with every correction and the phase of its sample.
+    pub fn on_correction<F: FnMut(Correction, u64) + 'static>(self, callback: F) -> Self {
+        Safeguard {
+            callback: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    pub fn report(&self) -> SafeguardReport {
+        self.report
+    }
+
+    /// Corrects `value`, the sample at `phase` of channel `channel`.
+    pub(crate) fn guard(&mut self, channel: usize, value: f64, phase: u64) -> f64 {
+        let mut value = value;
+        if !value.is_finite() {
+            self.correct(Correction::NonFinite, phase);
+            value = 0.0;
+        }
+        let ceiling = self.ceiling.abs();
+        if value.abs() > ceiling {
+            self.correct(Correction::Clipped, phase);
+            value = value.clamp(-ceiling, ceiling);
+        }
+
+        if self.channels.len() <= channel {
+            self.channels.resize(channel + 1, DcDetector::default());
+        }
+        let detector = &mut self.channels[channel];
+        let smoothing = smoothing(DC_AVERAGING_TIME, self.sample_rate);
+        detector.average = value + (detector.average - value) * smoothing;
+        detector.power = value * value + (detector.power - value * value) * smoothing;
+        let dc = detector.average.abs() > self.dc_threshold
+            && detector.average.abs() >= DC_SHARE * detector.power.sqrt();
+        let entered = dc && !detector.dc;
+        detector.dc = dc;
+        if dc {
+            self.report.dc_offset += 1;
+            if entered {
+                self.correct(Correction::DcOffset, phase);
+            }
+        }
+        value
+    }
+
+    fn correct(&mut self, correction: Correction, phase: u64) {
+        match correction {
+            Correction::NonFinite => self.report.non_finite += 1,
+            Correction::Clipped => self.report.clipped += 1,
+            Correction::DcOffset => {}
+        }
+        if let Some(callback) = &mut self.callback {
+            callback(correction, phase);
+        }
+    }
+}
+
+impl<T, C> Iterator for Safeguard<T>
+where
+    T: Iterator<Item = Sample<C>>,
+    C: Channels,
+{
+    type Item = Sample<C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| Sample {
+            data: sample
+                .data
+                .map_channels(|channel, value| self.guard(channel, value, sample.phase)),
+            phase: sample.phase,
+        })
+    }
+}
+
+/// Seeking starts the DC detection over.
+impl<T: Seek> Seek for Safeguard<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.channels.clear();
+        self.generator.seek(pos)
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{modulation::Modulation, Amplitude, Sample};
+
+/// Takes a new value from the stream `rate` times per second and repeats it until the next one,
+/// like playing the stream back at a lower sample rate without filtering it.
+#[derive(Clone, Copy)]
+pub struct SampleAndHold<T, R = f64> {
+    pub generator: T,
+    /// Values taken per second, at most one for every sample
+    pub rate: R,
+    pub sample_rate: u64,
+    held: f64,
+    /// Fraction of the time to the next value elapsed
+    elapsed: f64,
+}
+
+impl<T, R> SampleAndHold<T, R> {
+    pub fn new(generator: T, rate: R, sample_rate: u64) -> Self {
+        SampleAndHold {
+            generator,
+            rate,
+            sample_rate,
+            held: 0.0,
+            elapsed: 1.0,
+        }
+    }
+}
+
+impl<T, R> Iterator for SampleAndHold<T, R>
+where
+    T: Iterator<Item = Sample<Amplitude>>,
+    R: Modulation,
+{
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            // Allow for rounding, so a rate which divides the sample rate holds every value for
+            // the same number of samples.
+            if self.elapsed >= 1.0 - 1e-9 {
+                self.held = *sample;
+                self.elapsed = (self.elapsed - 1.0).max(0.0);
+            }
+            // Past one value per sample the time would build up faster than it can be used.
+            let rate = self.rate.next_value().max(0.0).min(self.sample_rate as f64);
+            self.elapsed += rate / self.sample_rate as f64;
+            Sample {
+                data: Amplitude(self.held),
+                ..sample
+            }
+        })
+    }
+}
+
+/// Takes a new value on the first sample after seeking.
+impl<T: Seek, R: Modulation> Seek for SampleAndHold<T, R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.generator.seek(pos)?;
+        self.rate.seek_to(position);
+        self.elapsed = 1.0;
+        Ok(position)
+    }
+}
@@ -0,0 +1,142 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{modulation::Modulation, Amplitude, Frequency, Sample};
+
+/// One of the outputs of a [`StateVariableFilter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateVariableMode {
+    Lowpass,
+    Bandpass,
+    Highpass,
+    Notch,
+}
+
+/// Every output of a [`StateVariableFilter`] for one sample.
+#[derive(Clone, Copy, Debug)]
+pub struct StateVariableOutputs {
+    pub lowpass: f64,
+    pub bandpass: f64,
+    pub highpass: f64,
+    pub notch: f64,
+}
+
+impl StateVariableOutputs {
+    pub fn get(&self, mode: StateVariableMode) -> f64 {
+        match mode {
+            StateVariableMode::Lowpass => self.lowpass,
+            StateVariableMode::Bandpass => self.bandpass,
+            StateVariableMode::Highpass => self.highpass,
+            StateVariableMode::Notch => self.notch,
+        }
+    }
+}
+
+/// Topology preserving transform state-variable filter, which stays stable however fast its
+/// cutoff moves.
+///
+/// The cutoff in Hz is taken from a stream of frequency samples, so it can be shaped with the
+/// same filters as an oscillator's frequency. Once that stream ends the last cutoff is kept.
+#[derive(Clone)]
+pub struct StateVariableFilter<T, C, R = f64> {
+    pub generator: T,
+    pub cutoff: C,
+    /// The Q factor, `0.707` is flat, higher values peak at the cutoff
+    pub resonance: R,
+    pub sample_rate: u64,
+    last_cutoff: f64,
+    integrators: [f64; 2],
+}
+
+impl<T, C, R> StateVariableFilter<T, C, R> {
+    pub fn new(generator: T, cutoff: C, resonance: R, sample_rate: u64) -> Self {
+        StateVariableFilter {
+            generator,
+            cutoff,
+            resonance,
+            sample_rate,
+            last_cutoff: 0.0,
+            integrators: [0.0; 2],
+        }
+    }
+
+    /// Keeps only one of the outputs, as a stream of amplitude samples.
+    pub fn output(self, mode: StateVariableMode) -> StateVariableOutput<Self> {
+        StateVariableOutput {
+            generator: self,
+            mode,
+        }
+    }
+}
+
+impl<T, C, R> Iterator for StateVariableFilter<T, C, R>
+where
+    T: Iterator<Item = Sample<Amplitude>>,
+    C: Iterator<Item = Sample<Frequency>>,
+    R: Modulation,
+{
+    type Item = Sample<StateVariableOutputs>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            if let Some(cutoff) = self.cutoff.next() {
+                self.last_cutoff = *cutoff;
+            }
+            let cutoff = self.last_cutoff.clamp(1.0, self.sample_rate as f64 * 0.49);
+            let g = (std::f64::consts::PI * cutoff / self.sample_rate as f64).tan();
+            let k = 1.0 / self.resonance.next_value().max(0.1);
+
+            let [band_state, low_state] = self.integrators;
+            let a1 = 1.0 / (1.0 + g * (g + k));
+            let a2 = g * a1;
+            let a3 = g * a2;
+            let v3 = *sample - low_state;
+            let bandpass = a1 * band_state + a2 * v3;
+            let lowpass = low_state + a2 * band_state + a3 * v3;
+            self.integrators = [2.0 * bandpass - band_state, 2.0 * lowpass - low_state];
+
+            let highpass = *sample - k * bandpass - lowpass;
+            Sample {
+                data: StateVariableOutputs {
+                    lowpass,
+                    bandpass,
+                    highpass,
+                    notch: lowpass + highpass,
+                },
+                phase: sample.phase,
+            }
+        })
+    }
+}
+
+impl<T: Seek, C: Seek, R: Modulation> Seek for StateVariableFilter<T, C, R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.generator.seek(pos)?;
+        self.cutoff.seek(SeekFrom::Start(position))?;
+        self.resonance.seek_to(position);
+        Ok(position)
+    }
+}
+
+/// One output of a [`StateVariableFilter`].
+#[derive(Clone)]
+pub struct StateVariableOutput<T> {
+    pub generator: T,
+    pub mode: StateVariableMode,
+}
+
+impl<T: Iterator<Item = Sample<StateVariableOutputs>>> Iterator for StateVariableOutput<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| Sample {
+            data: Amplitude(sample.data.get(self.mode)),
+            phase: sample.phase,
+        })
+    }
+}
+
+impl<T: Seek> Seek for StateVariableOutput<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
@@ -0,0 +1,69 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Amplitude, Sample};
+
+/// The shape of the volume changes of a [`Tremolo`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TremoloShape {
+    #[default]
+    Sine,
+    Triangle,
+    /// Switches between full and reduced volume, like a chopper
+    Square,
+    /// Drops to the reduced volume and slowly comes back up
+    Sawtooth,
+}
+
+impl TremoloShape {
+    /// The volume at `position` cycles in, from `0.0` for the quietest point to `1.0` for full
+    /// volume.
+    fn level(self, position: f64) -> f64 {
+        let position = position.rem_euclid(1.0);
+        match self {
+            TremoloShape::Sine => 0.5 + 0.5 * (std::f64::consts::TAU * position).cos(),
+            TremoloShape::Triangle => (2.0 * position - 1.0).abs(),
+            TremoloShape::Square => {
+                if position < 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            TremoloShape::Sawtooth => position,
+        }
+    }
+}
+
+/// Varies the volume `rate` times per second, the amplitude version of a vibrato. `depth` goes
+/// from `0.0` for no change to `1.0` for silence at the quietest point.
+#[derive(Clone, Copy)]
+pub struct Tremolo<T> {
+    pub generator: T,
+    pub rate: f64,
+    pub depth: f64,
+    pub shape: TremoloShape,
+    pub sample_rate: u64,
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Tremolo<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let level = self
+                .shape
+                .level(self.rate * sample.phase as f64 / self.sample_rate as f64);
+            let gain = 1.0 - self.depth.clamp(0.0, 1.0) * (1.0 - level);
+            Sample {
+                data: Amplitude(*sample * gain),
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek> Seek for Tremolo<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
@@ -15,10 +15,10 @@ impl<T: Iterator<Item = Sample<Frequency>>> Iterator for Vibrato<T> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.generator.next().map(|sample| {
-            let vibrato = (self.vibrato_depth
+            let vibrato = self.vibrato_depth
                 * (self.vibrato_speed * sample.phase as f64 / self.sample_rate as f64
                     * std::f64::consts::TAU)
-                    .sin()) as f64;
+                    .sin();
             Sample {
                 data: Frequency(*sample + vibrato),
                 ..sample
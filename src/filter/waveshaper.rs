@@ -0,0 +1,163 @@
+use std::{
+    fmt,
+    io::{Seek, SeekFrom},
+    rc::Rc,
+};
+
+use crate::{Amplitude, Sample};
+
+use super::biquad::{BiquadKind, Coefficients, DirectForm};
+
+/// The transfer function of a [`Waveshaper`].
+#[derive(Clone)]
+pub enum Shape {
+    /// Cuts everything past `-1.0..=1.0`
+    HardClip,
+    /// `tanh`, rounding off the peaks
+    SoftClip,
+    /// Folds everything past `-1.0..=1.0` back into range, getting brighter with more drive
+    Foldback,
+    /// Saturates positive values at `1.0` and negative ones earlier, at `-2/3`, adding even
+    /// harmonics and a small DC offset
+    Tube,
+    /// Any other transfer function, see [`Shape::custom`]
+    Custom(Rc<dyn Fn(f64) -> f64>),
+}
+
+impl Shape {
+    /// Any function or closure as the transfer function, closures may capture their settings.
+    pub fn custom<F: Fn(f64) -> f64 + 'static>(function: F) -> Self {
+        Shape::Custom(Rc::new(function))
+    }
+
+    pub fn apply(&self, value: f64) -> f64 {
+        match self {
+            Shape::HardClip => value.clamp(-1.0, 1.0),
+            Shape::SoftClip => value.tanh(),
+            Shape::Foldback => {
+                let folded = (value + 1.0).rem_euclid(4.0);
+                if folded < 2.0 {
+                    folded - 1.0
+                } else {
+                    3.0 - folded
+                }
+            }
+            Shape::Tube => {
+                if value >= 0.0 {
+                    1.0 - (-value).exp()
+                } else {
+                    ((1.5 * value).exp() - 1.0) / 1.5
+                }
+            }
+            Shape::Custom(function) => function(value),
+        }
+    }
+}
+
+impl fmt::Debug for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shape::HardClip => write!(f, "HardClip"),
+            Shape::SoftClip => write!(f, "SoftClip"),
+            Shape::Foldback => write!(f, "Foldback"),
+            Shape::Tube => write!(f, "Tube"),
+            Shape::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Distortion, multiplies the stream by `drive`, passes it through a [`Shape`] and multiplies it
+/// by `output_gain`.
+///
+/// Shaping adds harmonics which can fold back below the Nyquist frequency, running it
+/// [`oversampled`](Waveshaper::oversampled) removes most of them.
+#[derive(Clone)]
+pub struct Waveshaper<T> {
+    pub generator: T,
+    pub shape: Shape,
+    pub drive: f64,
+    pub output_gain: f64,
+    pub sample_rate: u64,
+    oversampling: usize,
+    previous: f64,
+    anti_aliasing: Coefficients,
+    filters: [DirectForm; 2],
+}
+
+impl<T> Waveshaper<T> {
+    pub fn new(generator: T, shape: Shape, drive: f64, output_gain: f64, sample_rate: u64) -> Self {
+        Waveshaper {
+            generator,
+            shape,
+            drive,
+            output_gain,
+            sample_rate,
+            oversampling: 1,
+            previous: 0.0,
+            anti_aliasing: anti_aliasing(sample_rate, 1),
+            filters: [DirectForm::default(); 2],
+        }
+    }
+
+    /// Shapes `factor` interpolated values per sample and low-passes them before keeping every
+    /// `factor`th one.
+    pub fn oversampled(self, factor: usize) -> Self {
+        let factor = factor.max(1);
+        Waveshaper {
+            oversampling: factor,
+            anti_aliasing: anti_aliasing(self.sample_rate, factor),
+            ..self
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let input = input * self.drive;
+        if self.oversampling == 1 {
+            return self.shape.apply(input) * self.output_gain;
+        }
+
+        let mut output = 0.0;
+        for step in 1..=self.oversampling {
+            let value =
+                self.previous + (input - self.previous) * step as f64 / self.oversampling as f64;
+            output = self.shape.apply(value);
+            for filter in &mut self.filters {
+                output = filter.process(&self.anti_aliasing, output);
+            }
+        }
+        self.previous = input;
+        output * self.output_gain
+    }
+}
+
+impl<T: Iterator<Item = Sample<Amplitude>>> Iterator for Waveshaper<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| Sample {
+            data: Amplitude(self.process(*sample)),
+            ..sample
+        })
+    }
+}
+
+/// Seeking clears the interpolation and the anti-aliasing filters, so the output doesn't depend
+/// on what played before.
+impl<T: Seek> Seek for Waveshaper<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.previous = 0.0;
+        self.filters = [DirectForm::default(); 2];
+        self.generator.seek(pos)
+    }
+}
+
+/// Low-pass just under the Nyquist frequency of `sample_rate`, run `factor` times faster.
+fn anti_aliasing(sample_rate: u64, factor: usize) -> Coefficients {
+    Coefficients::design(
+        BiquadKind::Lowpass,
+        sample_rate as f64 * 0.45,
+        std::f64::consts::FRAC_1_SQRT_2,
+        0.0,
+        sample_rate * factor as u64,
+    )
+}
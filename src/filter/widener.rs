@@ -0,0 +1,36 @@
+use std::io::{Seek, SeekFrom};
+
+use crate::{Sample, Stereo};
+
+/// Scales the difference between the channels by `width`, keeping what they share.
+///
+/// `0.0` makes the stream mono, `1.0` leaves it as it is and higher values make it wider.
+#[derive(Clone, Copy)]
+pub struct Widener<T> {
+    pub generator: T,
+    pub width: f64,
+}
+
+impl<T: Iterator<Item = Sample<Stereo>>> Iterator for Widener<T> {
+    type Item = Sample<Stereo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let mid = (sample.data.left + sample.data.right) / 2.0;
+            let side = (sample.data.left - sample.data.right) / 2.0 * self.width.max(0.0);
+            Sample {
+                data: Stereo {
+                    left: mid + side,
+                    right: mid - side,
+                },
+                ..sample
+            }
+        })
+    }
+}
+
+impl<T: Seek> Seek for Widener<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.generator.seek(pos)
+    }
+}
@@ -1,5 +1,7 @@
 use std::io::{Seek, SeekFrom};
 
+use rand::Rng;
+
 use crate::{Amplitude, Frequency, Sample};
 
 /// Produces a constant frequency, phase increments on every call to `next()`.
@@ -11,10 +13,10 @@ pub struct ConstantFrequencyGenerator {
 
 impl ConstantFrequencyGenerator {
     pub fn new(frequency: f64) -> Self {
-        return Self {
+        Self {
             frequency,
             phase: 0,
-        };
+        }
     }
 }
 
@@ -46,12 +48,23 @@ impl Seek for ConstantFrequencyGenerator {
 }
 
 /// Generates white noise.
+///
+/// Every sample is derived from `seed` and the position in the stream, so the same seed always
+/// produces the same noise, even after seeking.
 #[derive(Clone, Copy)]
-pub struct WhiteNoiseGenerator(u64);
+pub struct WhiteNoiseGenerator {
+    pub seed: u64,
+    phase: u64,
+}
 
 impl WhiteNoiseGenerator {
-    pub fn new() -> Self {
-        Self(0)
+    pub fn new(seed: u64) -> Self {
+        Self { seed, phase: 0 }
+    }
+
+    /// Seeds the generator from `rng`.
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(rng.gen())
     }
 }
 
@@ -59,10 +72,10 @@ impl Iterator for WhiteNoiseGenerator {
     type Item = Sample<Amplitude>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0 += 1;
+        self.phase += 1;
         Some(Sample {
-            data: Amplitude(rand::random()),
-            phase: self.0 - 1,
+            data: Amplitude(noise(self.seed, self.phase - 1)),
+            phase: self.phase - 1,
         })
     }
 }
@@ -71,13 +84,25 @@ impl Seek for WhiteNoiseGenerator {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
         match pos {
             SeekFrom::Start(pos) => {
-                self.0 = pos;
+                self.phase = pos;
             }
             SeekFrom::End(_) => return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput)),
             SeekFrom::Current(pos) => {
-                self.0 = (self.0 as i64 + pos) as u64;
+                self.phase = (self.phase as i64 + pos) as u64;
             }
         }
-        Ok(self.0)
+        Ok(self.phase)
     }
 }
+
+/// A uniformly distributed number in `0.0..1.0` for the `index`th value of the stream `seed`.
+///
+/// This is SplitMix64, which only uses integer arithmetic, so the result is the same on every
+/// machine.
+pub(crate) fn noise(seed: u64, index: u64) -> f64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -2,6 +2,9 @@ use std::ops::Deref;
 
 pub mod filter;
 pub mod generator;
+pub mod modulation;
+pub mod sfxr;
+pub mod wav;
 pub mod wave;
 
 /// A packet of frequency in Hz.
@@ -40,6 +43,23 @@ impl Deref for Amplitude {
     }
 }
 
+/// A packet of amplitude for each of the left and right channels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stereo {
+    pub left: f64,
+    pub right: f64,
+}
+
+impl From<Amplitude> for Stereo {
+    /// The same amplitude on both channels.
+    fn from(value: Amplitude) -> Self {
+        Stereo {
+            left: value.0,
+            right: value.0,
+        }
+    }
+}
+
 /// A Sample can contain either frequency or amplitude and the current state of the stream as phase.
 #[derive(Clone, Copy, Debug)]
 pub struct Sample<T> {
@@ -54,6 +74,6 @@ where
     type Target = f64;
 
     fn deref(&self) -> &Self::Target {
-        &(*self.data)
+        &self.data
     }
 }
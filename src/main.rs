@@ -13,7 +13,7 @@ fn main() {
     let square_wave = wave_builder.clone().square_wave(sample_rate, 0.5);
     let sine_wave = wave_builder.clone().sine_wave(sample_rate, 1.0);
     let sawtooth_wave = wave_builder.sawtooth_wave(sample_rate, 1.0);
-    let white_noise = WhiteNoiseGenerator::new();
+    let white_noise = WhiteNoiseGenerator::new(0);
 
     let pcm = PCM::new("default", Direction::Playback, false).unwrap();
     {
@@ -32,6 +32,7 @@ fn main() {
             square_wave
                 .envelope(0.0, 1.0, 1.0, 1.0, sample_rate)
                 .retrigger(2.0, 4, sample_rate)
+                .safeguard(sample_rate)
                 .map(|f| *f as f32)
                 .collect::<Vec<f32>>()
                 .as_slice(),
@@ -41,6 +42,7 @@ fn main() {
         io.writei(
             sawtooth_wave
                 .envelope(1.0, 1.0, 1.0, 1.0, sample_rate)
+                .safeguard(sample_rate)
                 .map(|f| *f as f32)
                 .collect::<Vec<f32>>()
                 .as_slice(),
@@ -51,6 +53,7 @@ fn main() {
             sine_wave
                 .envelope(0.0, 1.0, 1.0, 1.0, sample_rate)
                 .retrigger(2.0, 4, sample_rate)
+                .safeguard(sample_rate)
                 .map(|f| *f as f32)
                 .collect::<Vec<f32>>()
                 .as_slice(),
@@ -60,6 +63,7 @@ fn main() {
         io.writei(
             white_noise
                 .envelope(1.0, 1.0, 1.0, 1.0, sample_rate)
+                .safeguard(sample_rate)
                 .map(|f| *f as f32)
                 .collect::<Vec<f32>>()
                 .as_slice(),
@@ -0,0 +1,139 @@
+/// A parameter that can change on every sample, e.g. a duty cycle or a filter cutoff.
+///
+/// A plain `f64` is a parameter that never changes.
+pub trait Modulation {
+    /// Returns the value for the next sample.
+    fn next_value(&mut self) -> f64;
+
+    /// Moves to sample `position`, so the next value is the one for that sample. Parameters
+    /// which can't go back in time ignore this.
+    fn seek_to(&mut self, _position: u64) {}
+}
+
+impl Modulation for f64 {
+    fn next_value(&mut self) -> f64 {
+        *self
+    }
+}
+
+/// The shape of a [`Sweep`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Curve {
+    /// Adds the rate to the value every second.
+    Linear,
+    /// Multiplies the value by the rate every second.
+    Exponential,
+}
+
+/// A parameter that moves away from `start` at a constant rate, clamped to `min..=max`.
+#[derive(Clone, Copy, Debug)]
+pub struct Sweep {
+    pub start: f64,
+    pub rate: f64,
+    pub curve: Curve,
+    pub min: f64,
+    pub max: f64,
+    /// Number of samples after which the sweep starts over, 0 never starts over.
+    pub period: u64,
+    pub sample_rate: u64,
+    position: u64,
+}
+
+impl Sweep {
+    /// Adds `rate` to `start` every second.
+    pub fn linear(start: f64, rate: f64, sample_rate: u64) -> Self {
+        Sweep {
+            start,
+            rate,
+            curve: Curve::Linear,
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
+            period: 0,
+            sample_rate,
+            position: 0,
+        }
+    }
+
+    /// Multiplies `start` by `rate` every second. A `rate` below zero, or NaN, is taken as zero.
+    pub fn exponential(start: f64, rate: f64, sample_rate: u64) -> Self {
+        Sweep {
+            curve: Curve::Exponential,
+            ..Sweep::linear(start, rate, sample_rate)
+        }
+    }
+
+    /// Keeps the value within `min..=max`, the bounds can be given in either order.
+    pub fn clamped(self, min: f64, max: f64) -> Self {
+        Sweep {
+            min: min.min(max),
+            max: min.max(max),
+            ..self
+        }
+    }
+
+    /// Starts the sweep over `rate` times a second, in step with
+    /// [`FrequencyDomainFilterable::repeat`](crate::filter::FrequencyDomainFilterable::repeat).
+    pub fn repeating(self, rate: f64) -> Self {
+        Sweep {
+            period: if rate > 0.0 {
+                (self.sample_rate as f64 / rate).round() as u64
+            } else {
+                0
+            },
+            ..self
+        }
+    }
+
+    /// The value at sample `position`.
+    pub fn value_at(&self, position: u64) -> f64 {
+        let position = position.checked_rem(self.period).unwrap_or(position);
+        let time = position as f64 / self.sample_rate as f64;
+        let value = match self.curve {
+            Curve::Linear => self.start + self.rate * time,
+            Curve::Exponential => self.start * self.rate.max(0.0).powf(time),
+        };
+        // The bounds are public and may have been swapped or set to NaN since `clamped`, which
+        // would make `f64::clamp` panic.
+        value
+            .max(self.min.min(self.max))
+            .min(self.max.max(self.min))
+    }
+}
+
+impl Modulation for Sweep {
+    fn next_value(&mut self) -> f64 {
+        self.position += 1;
+        self.value_at(self.position - 1)
+    }
+
+    fn seek_to(&mut self, position: u64) {
+        self.position = position;
+    }
+}
+
+/// Takes the value for every sample from an iterator, e.g. an LFO, and keeps the last value
+/// once it runs out.
+#[derive(Clone, Copy, Debug)]
+pub struct Modulator<I> {
+    pub values: I,
+    last: f64,
+}
+
+impl<I: Iterator<Item = f64>> Modulator<I> {
+    /// `initial` is used if `values` is empty.
+    pub fn new(values: I, initial: f64) -> Self {
+        Modulator {
+            values,
+            last: initial,
+        }
+    }
+}
+
+impl<I: Iterator<Item = f64>> Modulation for Modulator<I> {
+    fn next_value(&mut self) -> f64 {
+        if let Some(value) = self.values.next() {
+            self.last = value;
+        }
+        self.last
+    }
+}
@@ -0,0 +1,894 @@
+use std::{io::Seek, ops::RangeInclusive};
+
+use rand::Rng;
+
+use crate::{
+    filter::{safeguard::Safeguard, AmplitudeDomainFilterable, FrequencyDomainFilterable},
+    generator::ConstantFrequencyGenerator,
+    modulation::Sweep,
+    wave::AntiAliasing,
+    Amplitude, Frequency, Sample,
+};
+
+/// jsfxr measures all of its times in samples at this rate, regardless of the output rate.
+const JSFXR_SAMPLE_RATE: f64 = 44100.0;
+
+/// jsfxr runs its oscillators at 8 times its sample rate.
+const JSFXR_SUPERSAMPLING: f64 = 8.0;
+
+/// jsfxr never lets the oscillator period drop below this many supersamples.
+const JSFXR_MIN_PERIOD: f64 = 8.0;
+
+/// The longest delay of jsfxr's phaser, in supersamples.
+const PHASER_MAX_DELAY: f64 = 1023.0;
+
+/// jsfxr reads 32 noise values per period of the oscillator.
+const NOISE_VALUES_PER_PERIOD: u32 = 32;
+
+/// The shape of the oscillator used by [`SfxrParams`].
+///
+/// The first four are jsfxr's, the rest follow bfxr's numbering.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WaveType {
+    #[default]
+    Square,
+    Sawtooth,
+    Sine,
+    Noise,
+    Triangle,
+    PinkNoise,
+    Tan,
+    Whistle,
+    Breaker,
+    BitNoise,
+    BrownNoise,
+}
+
+/// Names a single field of [`SfxrParams`], e.g. to lock it while mutating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Param {
+    WaveType,
+    BaseFreq,
+    FreqLimit,
+    FreqRamp,
+    FreqDramp,
+    VibStrength,
+    VibSpeed,
+    ArpMod,
+    ArpSpeed,
+    Duty,
+    DutyRamp,
+    RepeatSpeed,
+    PhaOffset,
+    PhaRamp,
+    LpfFreq,
+    LpfRamp,
+    LpfResonance,
+    HpfFreq,
+    HpfRamp,
+    EnvAttack,
+    EnvSustain,
+    EnvPunch,
+    EnvDecay,
+    SoundVol,
+}
+
+impl Param {
+    pub const ALL: [Param; 24] = [
+        Param::WaveType,
+        Param::BaseFreq,
+        Param::FreqLimit,
+        Param::FreqRamp,
+        Param::FreqDramp,
+        Param::VibStrength,
+        Param::VibSpeed,
+        Param::ArpMod,
+        Param::ArpSpeed,
+        Param::Duty,
+        Param::DutyRamp,
+        Param::RepeatSpeed,
+        Param::PhaOffset,
+        Param::PhaRamp,
+        Param::LpfFreq,
+        Param::LpfRamp,
+        Param::LpfResonance,
+        Param::HpfFreq,
+        Param::HpfRamp,
+        Param::EnvAttack,
+        Param::EnvSustain,
+        Param::EnvPunch,
+        Param::EnvDecay,
+        Param::SoundVol,
+    ];
+
+    /// The values jsfxr allows for the parameter.
+    pub fn range(self) -> RangeInclusive<f64> {
+        match self {
+            Param::FreqRamp
+            | Param::FreqDramp
+            | Param::ArpMod
+            | Param::DutyRamp
+            | Param::PhaOffset
+            | Param::PhaRamp
+            | Param::LpfRamp
+            | Param::HpfRamp => -1.0..=1.0,
+            Param::WaveType => 0.0..=10.0,
+            _ => 0.0..=1.0,
+        }
+    }
+}
+
+/// The full set of jsfxr parameters.
+///
+/// Every field uses the same normalized range as jsfxr, given on each field, so values can be
+/// copied over from jsfxr as they are.
+///
+/// The category presets follow jsfxr, except that most of them turn `sound_vol` down from 0.5,
+/// where jsfxr's punch, phaser and filters clip the majority of their sounds. A strong low-pass
+/// resonance can still clip a `synth`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SfxrParams {
+    pub wave_type: WaveType,
+    /// Start frequency, `0.0..=1.0`
+    pub base_freq: f64,
+    /// Min frequency cutoff, `0.0..=1.0`, the sound stops when the frequency drops below it
+    pub freq_limit: f64,
+    /// Slide, `-1.0..=1.0`, negative values slide down
+    pub freq_ramp: f64,
+    /// Delta slide, `-1.0..=1.0`, negative values slow a slide down
+    pub freq_dramp: f64,
+    /// Vibrato depth, `0.0..=1.0`
+    pub vib_strength: f64,
+    /// Vibrato speed, `0.0..=1.0`
+    pub vib_speed: f64,
+    /// Change amount, `-1.0..=1.0`, negative values lower the pitch
+    pub arp_mod: f64,
+    /// Change speed, `0.0..=1.0`
+    pub arp_speed: f64,
+    /// Square duty, `0.0..=1.0`
+    pub duty: f64,
+    /// Duty sweep, `-1.0..=1.0`
+    pub duty_ramp: f64,
+    /// Repeat speed, `0.0..=1.0`
+    pub repeat_speed: f64,
+    /// Phaser offset, `-1.0..=1.0`
+    pub pha_offset: f64,
+    /// Phaser sweep, `-1.0..=1.0`
+    pub pha_ramp: f64,
+    /// Low-pass filter cutoff, `0.0..=1.0`
+    pub lpf_freq: f64,
+    /// Low-pass filter cutoff sweep, `-1.0..=1.0`
+    pub lpf_ramp: f64,
+    /// Low-pass filter resonance, `0.0..=1.0`
+    pub lpf_resonance: f64,
+    /// High-pass filter cutoff, `0.0..=1.0`
+    pub hpf_freq: f64,
+    /// High-pass filter cutoff sweep, `-1.0..=1.0`
+    pub hpf_ramp: f64,
+    /// Attack time, `0.0..=1.0`
+    pub env_attack: f64,
+    /// Sustain time, `0.0..=1.0`
+    pub env_sustain: f64,
+    /// Sustain punch, `0.0..=1.0`
+    pub env_punch: f64,
+    /// Decay time, `0.0..=1.0`
+    pub env_decay: f64,
+    /// Volume, `0.0..=1.0`
+    pub sound_vol: f64,
+    pub sample_rate: u64,
+    /// Seeds every random source used while rendering, the same parameters always render the
+    /// same samples.
+    pub seed: u64,
+    /// How the oscillator is kept from aliasing, jsfxr supersamples 8 times.
+    pub anti_aliasing: AntiAliasing,
+}
+
+impl Default for SfxrParams {
+    fn default() -> Self {
+        Self {
+            wave_type: WaveType::Square,
+            base_freq: 0.3,
+            freq_limit: 0.0,
+            freq_ramp: 0.0,
+            freq_dramp: 0.0,
+            vib_strength: 0.0,
+            vib_speed: 0.0,
+            arp_mod: 0.0,
+            arp_speed: 0.0,
+            duty: 0.0,
+            duty_ramp: 0.0,
+            repeat_speed: 0.0,
+            pha_offset: 0.0,
+            pha_ramp: 0.0,
+            lpf_freq: 1.0,
+            lpf_ramp: 0.0,
+            lpf_resonance: 0.0,
+            hpf_freq: 0.0,
+            hpf_ramp: 0.0,
+            env_attack: 0.0,
+            env_sustain: 0.3,
+            env_punch: 0.0,
+            env_decay: 0.4,
+            sound_vol: 0.5,
+            sample_rate: 44100,
+            seed: 0,
+            anti_aliasing: AntiAliasing::Supersample(8),
+        }
+    }
+}
+
+impl SfxrParams {
+    /// The default parameters with `seed` drawn from `rng`.
+    ///
+    /// Every preset only draws from `rng`, so a preset and a seedable `rng` such as
+    /// `StdRng::seed_from_u64` always produce the same sound.
+    pub fn seeded<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            seed: rng.gen(),
+            ..Default::default()
+        }
+    }
+
+    /// A short, bright blip with an optional jump in pitch.
+    pub fn pickup_coin<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: WaveType::Sawtooth,
+            base_freq: 0.4 + frnd(rng, 0.5),
+            env_attack: 0.0,
+            env_sustain: frnd(rng, 0.1),
+            env_decay: 0.1 + frnd(rng, 0.4),
+            env_punch: 0.3 + frnd(rng, 0.3),
+            sound_vol: 0.39,
+            ..Self::seeded(rng)
+        };
+        if rnd(rng, 1) == 1 {
+            params.arp_speed = 0.5 + frnd(rng, 0.2);
+            params.arp_mod = 0.2 + frnd(rng, 0.4);
+        }
+        params
+    }
+
+    /// A falling zap.
+    pub fn laser_shoot<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: wave_type(rnd(rng, 2)),
+            ..Self::seeded(rng)
+        };
+        if params.wave_type == WaveType::Sine && rnd(rng, 1) == 1 {
+            params.wave_type = wave_type(rnd(rng, 1));
+        }
+        if rnd(rng, 2) == 0 {
+            params.base_freq = 0.3 + frnd(rng, 0.6);
+            params.freq_limit = frnd(rng, 0.1);
+            params.freq_ramp = -0.35 - frnd(rng, 0.3);
+        } else {
+            params.base_freq = 0.5 + frnd(rng, 0.5);
+            params.freq_limit = (params.base_freq - 0.2 - frnd(rng, 0.6)).max(0.2);
+            params.freq_ramp = -0.15 - frnd(rng, 0.2);
+        }
+        if params.wave_type == WaveType::Sawtooth {
+            params.duty = 1.0;
+        }
+        if rnd(rng, 1) == 1 {
+            params.duty = frnd(rng, 0.5);
+            params.duty_ramp = frnd(rng, 0.2);
+        } else {
+            params.duty = 0.4 + frnd(rng, 0.5);
+            params.duty_ramp = -frnd(rng, 0.7);
+        }
+        params.env_attack = 0.0;
+        params.env_sustain = 0.1 + frnd(rng, 0.2);
+        params.env_decay = frnd(rng, 0.4);
+        if rnd(rng, 1) == 1 {
+            params.env_punch = frnd(rng, 0.3);
+        }
+        if rnd(rng, 2) == 0 {
+            params.pha_offset = frnd(rng, 0.2);
+            params.pha_ramp = -frnd(rng, 0.2);
+        }
+        params.hpf_freq = frnd(rng, 0.3);
+        params.sound_vol = 0.23;
+        params
+    }
+
+    /// A burst of noise.
+    pub fn explosion<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: WaveType::Noise,
+            ..Self::seeded(rng)
+        };
+        if rnd(rng, 1) == 1 {
+            params.base_freq = (0.1 + frnd(rng, 0.4)).powi(2);
+            params.freq_ramp = -0.1 + frnd(rng, 0.4);
+        } else {
+            params.base_freq = (0.2 + frnd(rng, 0.7)).powi(2);
+            params.freq_ramp = -0.2 - frnd(rng, 0.2);
+        }
+        if rnd(rng, 4) == 0 {
+            params.freq_ramp = 0.0;
+        }
+        if rnd(rng, 2) == 0 {
+            params.repeat_speed = 0.3 + frnd(rng, 0.5);
+        }
+        params.env_attack = 0.0;
+        params.env_sustain = 0.1 + frnd(rng, 0.3);
+        params.env_decay = frnd(rng, 0.5);
+        if rnd(rng, 1) == 1 {
+            params.pha_offset = -0.3 + frnd(rng, 0.9);
+            params.pha_ramp = -frnd(rng, 0.3);
+        }
+        params.env_punch = 0.2 + frnd(rng, 0.6);
+        if rnd(rng, 1) == 1 {
+            params.vib_strength = frnd(rng, 0.7);
+            params.vib_speed = frnd(rng, 0.6);
+        }
+        if rnd(rng, 2) == 0 {
+            params.arp_speed = 0.6 + frnd(rng, 0.3);
+            params.arp_mod = 0.8 - frnd(rng, 1.6);
+        }
+        params.sound_vol = 0.2;
+        params
+    }
+
+    /// A rising, often repeating tone.
+    pub fn powerup<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self::seeded(rng);
+        if rnd(rng, 1) == 1 {
+            params.wave_type = WaveType::Sawtooth;
+            params.duty = 1.0;
+        } else {
+            params.duty = frnd(rng, 0.6);
+        }
+        params.base_freq = 0.2 + frnd(rng, 0.3);
+        if rnd(rng, 1) == 1 {
+            params.freq_ramp = 0.1 + frnd(rng, 0.4);
+            params.repeat_speed = 0.4 + frnd(rng, 0.4);
+        } else {
+            params.freq_ramp = 0.05 + frnd(rng, 0.2);
+            if rnd(rng, 1) == 1 {
+                params.vib_strength = frnd(rng, 0.7);
+                params.vib_speed = frnd(rng, 0.6);
+            }
+        }
+        params.env_attack = 0.0;
+        params.env_sustain = frnd(rng, 0.4);
+        params.env_decay = 0.1 + frnd(rng, 0.4);
+        params
+    }
+
+    /// A short falling thud.
+    pub fn hit_hurt<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: wave_type(rnd(rng, 2)),
+            ..Self::seeded(rng)
+        };
+        match params.wave_type {
+            WaveType::Sine => params.wave_type = WaveType::Noise,
+            WaveType::Square => params.duty = frnd(rng, 0.6),
+            WaveType::Sawtooth => params.duty = 1.0,
+            _ => {}
+        }
+        params.base_freq = 0.2 + frnd(rng, 0.6);
+        params.freq_ramp = -0.3 - frnd(rng, 0.4);
+        params.env_attack = 0.0;
+        params.env_sustain = frnd(rng, 0.1);
+        params.env_decay = 0.1 + frnd(rng, 0.2);
+        if rnd(rng, 1) == 1 {
+            params.hpf_freq = frnd(rng, 0.3);
+        }
+        params.sound_vol = 0.44;
+        params
+    }
+
+    /// A rising square wave.
+    pub fn jump<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: WaveType::Square,
+            duty: frnd(rng, 0.6),
+            base_freq: 0.3 + frnd(rng, 0.3),
+            freq_ramp: 0.1 + frnd(rng, 0.2),
+            env_attack: 0.0,
+            env_sustain: 0.1 + frnd(rng, 0.3),
+            env_decay: 0.1 + frnd(rng, 0.2),
+            sound_vol: 0.42,
+            ..Self::seeded(rng)
+        };
+        if rnd(rng, 1) == 1 {
+            params.hpf_freq = frnd(rng, 0.3);
+        }
+        if rnd(rng, 1) == 1 {
+            params.lpf_freq = 1.0 - frnd(rng, 0.6);
+        }
+        params
+    }
+
+    /// A very short click for menus.
+    pub fn blip_select<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: wave_type(rnd(rng, 1)),
+            ..Self::seeded(rng)
+        };
+        params.duty = if params.wave_type == WaveType::Square {
+            frnd(rng, 0.6)
+        } else {
+            1.0
+        };
+        params.base_freq = 0.2 + frnd(rng, 0.4);
+        params.env_attack = 0.0;
+        params.env_sustain = 0.1 + frnd(rng, 0.1);
+        params.env_decay = frnd(rng, 0.2);
+        params.hpf_freq = 0.1;
+        params.sound_vol = 0.46;
+        params
+    }
+
+    /// A musical note with a random envelope and filter.
+    pub fn synth<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            wave_type: wave_type(rnd(rng, 1)),
+            base_freq: [0.2723171360931539, 0.19255692561524382, 0.13615778746815113]
+                [rnd(rng, 2) as usize],
+            env_attack: if rnd(rng, 4) > 3 { frnd(rng, 0.5) } else { 0.0 },
+            env_sustain: frnd(rng, 1.0),
+            env_punch: frnd(rng, 1.0),
+            env_decay: frnd(rng, 0.9) + 0.1,
+            arp_mod: [0.0, 0.0, 0.0, 0.0, -0.3162, 0.7454, 0.7454][rnd(rng, 6) as usize],
+            arp_speed: frnd(rng, 0.5) + 0.4,
+            duty: frnd(rng, 1.0),
+            duty_ramp: if rnd(rng, 2) == 2 {
+                frnd(rng, 1.0)
+            } else {
+                0.0
+            },
+            lpf_freq: if rnd(rng, 1) == 1 {
+                0.9 * frnd(rng, 1.0) * frnd(rng, 1.0) + 0.1
+            } else {
+                1.0
+            },
+            lpf_ramp: rndr(rng, -1.0, 1.0),
+            lpf_resonance: frnd(rng, 1.0),
+            hpf_freq: if rnd(rng, 3) == 3 {
+                frnd(rng, 1.0)
+            } else {
+                0.0
+            },
+            hpf_ramp: if rnd(rng, 3) == 3 {
+                frnd(rng, 1.0)
+            } else {
+                0.0
+            },
+            sound_vol: 0.13,
+            ..Self::seeded(rng)
+        }
+    }
+
+    /// A plain 440 Hz sine wave lasting one second.
+    pub fn tone<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            wave_type: WaveType::Sine,
+            base_freq: 0.35173364,
+            env_attack: 0.0,
+            env_sustain: 0.6641,
+            env_decay: 0.0,
+            env_punch: 0.0,
+            ..Self::seeded(rng)
+        }
+    }
+
+    /// Randomizes every parameter.
+    ///
+    /// Like jsfxr, this can leave parameters outside [`Param::range`], e.g. a negative duty or
+    /// envelope time, and most of its sounds clip at the default volume.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut params = Self {
+            wave_type: wave_type(rnd(rng, 3)),
+            ..Self::seeded(rng)
+        };
+        params.base_freq = if rnd(rng, 1) == 1 {
+            (frnd(rng, 2.0) - 1.0).powi(3) + 0.5
+        } else {
+            frnd(rng, 1.0).powi(2)
+        };
+        params.freq_limit = 0.0;
+        params.freq_ramp = (frnd(rng, 2.0) - 1.0).powi(5);
+        if params.base_freq > 0.7 && params.freq_ramp > 0.2 {
+            params.freq_ramp = -params.freq_ramp;
+        }
+        if params.base_freq < 0.2 && params.freq_ramp < -0.05 {
+            params.freq_ramp = -params.freq_ramp;
+        }
+        params.freq_dramp = (frnd(rng, 2.0) - 1.0).powi(3);
+        params.duty = frnd(rng, 2.0) - 1.0;
+        params.duty_ramp = (frnd(rng, 2.0) - 1.0).powi(3);
+        params.vib_strength = (frnd(rng, 2.0) - 1.0).powi(3);
+        params.vib_speed = rndr(rng, -1.0, 1.0);
+        params.env_attack = rndr(rng, -1.0, 1.0).powi(3);
+        params.env_sustain = rndr(rng, -1.0, 1.0).powi(2);
+        params.env_decay = rndr(rng, -1.0, 1.0);
+        params.env_punch = frnd(rng, 0.8).powi(2);
+        if params.env_attack + params.env_sustain + params.env_decay < 0.2 {
+            params.env_sustain += 0.2 + frnd(rng, 0.3);
+            params.env_decay += 0.2 + frnd(rng, 0.3);
+        }
+        params.lpf_resonance = rndr(rng, -1.0, 1.0);
+        params.lpf_freq = 1.0 - frnd(rng, 1.0).powi(3);
+        params.lpf_ramp = (frnd(rng, 2.0) - 1.0).powi(3);
+        if params.lpf_freq < 0.1 && params.lpf_ramp < -0.05 {
+            params.lpf_ramp = -params.lpf_ramp;
+        }
+        params.hpf_freq = frnd(rng, 1.0).powi(5);
+        params.hpf_ramp = (frnd(rng, 2.0) - 1.0).powi(5);
+        params.pha_offset = (frnd(rng, 2.0) - 1.0).powi(3);
+        params.pha_ramp = (frnd(rng, 2.0) - 1.0).powi(3);
+        params.repeat_speed = frnd(rng, 2.0) - 1.0;
+        params.arp_speed = frnd(rng, 2.0) - 1.0;
+        params.arp_mod = frnd(rng, 2.0) - 1.0;
+        params
+    }
+
+    /// The value of `param`, wave types are numbered like in jsfxr.
+    pub fn get(&self, param: Param) -> f64 {
+        match param {
+            Param::WaveType => self.wave_type as u32 as f64,
+            param => {
+                let mut params = *self;
+                *params.value_mut(param).unwrap()
+            }
+        }
+    }
+
+    /// Sets `param` to `value`, clamped to [`Param::range`].
+    pub fn set(&mut self, param: Param, value: f64) {
+        let value = value.clamp(*param.range().start(), *param.range().end());
+        match self.value_mut(param) {
+            Some(field) => *field = value,
+            None => self.wave_type = wave_type(value.round() as u32),
+        }
+    }
+
+    fn value_mut(&mut self, param: Param) -> Option<&mut f64> {
+        match param {
+            Param::WaveType => None,
+            Param::BaseFreq => Some(&mut self.base_freq),
+            Param::FreqLimit => Some(&mut self.freq_limit),
+            Param::FreqRamp => Some(&mut self.freq_ramp),
+            Param::FreqDramp => Some(&mut self.freq_dramp),
+            Param::VibStrength => Some(&mut self.vib_strength),
+            Param::VibSpeed => Some(&mut self.vib_speed),
+            Param::ArpMod => Some(&mut self.arp_mod),
+            Param::ArpSpeed => Some(&mut self.arp_speed),
+            Param::Duty => Some(&mut self.duty),
+            Param::DutyRamp => Some(&mut self.duty_ramp),
+            Param::RepeatSpeed => Some(&mut self.repeat_speed),
+            Param::PhaOffset => Some(&mut self.pha_offset),
+            Param::PhaRamp => Some(&mut self.pha_ramp),
+            Param::LpfFreq => Some(&mut self.lpf_freq),
+            Param::LpfRamp => Some(&mut self.lpf_ramp),
+            Param::LpfResonance => Some(&mut self.lpf_resonance),
+            Param::HpfFreq => Some(&mut self.hpf_freq),
+            Param::HpfRamp => Some(&mut self.hpf_ramp),
+            Param::EnvAttack => Some(&mut self.env_attack),
+            Param::EnvSustain => Some(&mut self.env_sustain),
+            Param::EnvPunch => Some(&mut self.env_punch),
+            Param::EnvDecay => Some(&mut self.env_decay),
+            Param::SoundVol => Some(&mut self.sound_vol),
+        }
+    }
+
+    /// Moves every parameter but the wave type by up to `amount` in either direction, like
+    /// jsfxr's mutate button.
+    pub fn mutate<R: Rng + ?Sized>(&mut self, amount: f64, rng: &mut R) {
+        self.mutate_locked(amount, &[], rng)
+    }
+
+    /// Same as [`SfxrParams::mutate`], but leaves the parameters in `locked` as they are.
+    pub fn mutate_locked<R: Rng + ?Sized>(&mut self, amount: f64, locked: &[Param], rng: &mut R) {
+        let unlocked: Vec<Param> = Param::ALL
+            .into_iter()
+            .filter(|param| *param != Param::WaveType && !locked.contains(param))
+            .collect();
+        self.mutate_unlocked(amount, &unlocked, rng)
+    }
+
+    /// Moves only the parameters in `unlocked` by up to `amount` in either direction.
+    ///
+    /// `amount` is clamped to `0.0..=1.0`, NaN counts as 0. Each parameter stays within
+    /// [`Param::range`], or within its current value if that is already outside the range, as
+    /// presets like [`SfxrParams::random`] can leave it. The wave type has no neighbouring values
+    /// to wander to, so when it is unlocked it is replaced by a random one with a probability of
+    /// `amount` instead.
+    pub fn mutate_unlocked<R: Rng + ?Sized>(
+        &mut self,
+        amount: f64,
+        unlocked: &[Param],
+        rng: &mut R,
+    ) {
+        let amount = if amount.is_nan() {
+            0.0
+        } else {
+            amount.clamp(0.0, 1.0)
+        };
+        for param in Param::ALL {
+            if !unlocked.contains(&param) {
+                continue;
+            }
+            match param {
+                Param::WaveType => {
+                    if rng.gen_bool(amount) {
+                        self.wave_type = wave_type(rnd(rng, 10));
+                    }
+                }
+                param => {
+                    if rnd(rng, 1) == 1 {
+                        let range = param.range();
+                        let field = self.value_mut(param).unwrap();
+                        let moved = *field + frnd(rng, amount * 2.0) - amount;
+                        if moved != *field {
+                            *field =
+                                moved.clamp(range.start().min(*field), range.end().max(*field));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds the whole chain of generators and filters described by the parameters.
+    ///
+    /// The chain ends in a [`Safeguard`], which can be given a callback or a different ceiling and
+    /// keeps a report of what it corrected.
+    pub fn render(&self) -> Safeguard<Box<dyn Iterator<Item = Sample<Amplitude>>>> {
+        let shaped = match self.wave_type {
+            WaveType::Square => self.shape(
+                self.frequency_stream()
+                    .square_wave(
+                        self.sample_rate,
+                        Sweep::linear(
+                            0.5 - self.duty * 0.5,
+                            -self.duty_ramp * 0.00005 * JSFXR_SAMPLE_RATE,
+                            self.sample_rate,
+                        )
+                        .clamped(0.0, 0.5)
+                        .repeating(self.repeat_rate()),
+                    )
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::Sawtooth => self.shape(
+                self.frequency_stream()
+                    .sawtooth_wave(self.sample_rate, 1.0)
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::Sine => self.shape(
+                self.frequency_stream()
+                    .sine_wave(self.sample_rate, 1.0)
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::Noise => self.shape(
+                self.frequency_stream()
+                    .noise_wave(self.sample_rate, self.seed)
+                    .values_per_period(NOISE_VALUES_PER_PERIOD),
+            ),
+            WaveType::Triangle => self.shape(
+                self.frequency_stream()
+                    .triangle_wave(self.sample_rate)
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::PinkNoise => self.shape(
+                self.frequency_stream()
+                    .pink_noise(self.sample_rate, self.seed)
+                    .values_per_period(NOISE_VALUES_PER_PERIOD),
+            ),
+            WaveType::Tan => self.shape(
+                self.frequency_stream()
+                    .tan_wave(self.sample_rate)
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::Whistle => self.shape(
+                self.frequency_stream()
+                    .whistle_wave(self.sample_rate)
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::Breaker => self.shape(
+                self.frequency_stream()
+                    .breaker_wave(self.sample_rate)
+                    .anti_aliased(self.anti_aliasing),
+            ),
+            WaveType::BitNoise => self.shape(
+                self.frequency_stream()
+                    .bit_noise(self.sample_rate)
+                    .values_per_period(NOISE_VALUES_PER_PERIOD),
+            ),
+            WaveType::BrownNoise => self.shape(
+                self.frequency_stream()
+                    .brown_noise(self.sample_rate, self.seed)
+                    .values_per_period(NOISE_VALUES_PER_PERIOD),
+            ),
+        };
+        shaped.safeguard(self.sample_rate)
+    }
+
+    fn frequency_stream(&self) -> impl Iterator<Item = Sample<Frequency>> + Seek + Clone {
+        let frequency = period_to_frequency(100.0 / (self.base_freq.powi(2) + 0.001));
+
+        // jsfxr multiplies the period on every sample and changes the multiplier linearly, so
+        // the frequency slides exponentially. A linear slide would drift away from it and reach
+        // 0 Hz on long downward slides.
+        let period_mult = 1.0 - self.freq_ramp.powi(3) * 0.01;
+        let period_mult_slide = -self.freq_dramp.powi(3) * 0.000001;
+        let slide = -period_mult.ln() * JSFXR_SAMPLE_RATE;
+        let slide_delta = -period_mult_slide / period_mult * JSFXR_SAMPLE_RATE.powi(2);
+
+        let min_frequency = if self.freq_limit > 0.0 {
+            period_to_frequency(100.0 / (self.freq_limit.powi(2) + 0.001))
+        } else {
+            0.0
+        };
+
+        let vibrato_speed =
+            self.vib_speed.powi(2) * 0.01 * JSFXR_SAMPLE_RATE / std::f64::consts::TAU;
+        let vibrato_depth = frequency * self.vib_strength * 0.5;
+
+        let arp_mult = if self.arp_mod >= 0.0 {
+            1.0 - self.arp_mod.powi(2) * 0.9
+        } else {
+            1.0 + self.arp_mod.powi(2) * 10.0
+        };
+        let arp_delay = if self.arp_speed == 1.0 {
+            f64::INFINITY
+        } else {
+            ((1.0 - self.arp_speed).powi(2) * 20000.0 + 32.0).floor() / JSFXR_SAMPLE_RATE
+        };
+
+        ConstantFrequencyGenerator::new(frequency)
+            .exponential_slide(slide, slide_delta, self.sample_rate)
+            .min_cutoff(min_frequency)
+            .vibrato(vibrato_speed, vibrato_depth, self.sample_rate)
+            .arpeggiation(1.0 / arp_mult, arp_delay, self.sample_rate)
+            .max_frequency(period_to_frequency(JSFXR_MIN_PERIOD))
+            .repeat(self.repeat_rate(), self.sample_rate)
+    }
+
+    /// How often the pitch and the duty sweep start over, in Hz.
+    fn repeat_rate(&self) -> f64 {
+        if self.repeat_speed == 0.0 {
+            0.0
+        } else {
+            JSFXR_SAMPLE_RATE / ((1.0 - self.repeat_speed).powi(2) * 20000.0 + 32.0).floor()
+        }
+    }
+
+    fn shape<W>(&self, wave: W) -> Box<dyn Iterator<Item = Sample<Amplitude>>>
+    where
+        W: Iterator<Item = Sample<Amplitude>> + 'static,
+    {
+        let enveloped = wave.envelope(
+            envelope_time(self.env_attack),
+            envelope_time(self.env_sustain),
+            1.0 + 2.0 * self.env_punch,
+            envelope_time(self.env_decay),
+            self.sample_rate,
+        );
+
+        // jsfxr's phaser delay is counted in supersamples.
+        let supersample_rate = JSFXR_SUPERSAMPLING * JSFXR_SAMPLE_RATE;
+
+        let phased: Box<dyn Iterator<Item = Sample<Amplitude>>> =
+            if self.pha_offset != 0.0 || self.pha_ramp != 0.0 {
+                let delay = self.pha_offset.powi(2).copysign(self.pha_offset) * 1020.0;
+                let sweep = self.pha_ramp.powi(2).copysign(self.pha_ramp) * JSFXR_SAMPLE_RATE;
+                Box::new(enveloped.phaser(
+                    delay / supersample_rate,
+                    sweep / supersample_rate,
+                    PHASER_MAX_DELAY / supersample_rate,
+                    0.0,
+                    1.0,
+                    self.sample_rate,
+                ))
+            } else {
+                Box::new(enveloped)
+            };
+
+        // jsfxr moves the low-pass coefficient once per supersample, but the high-pass one only
+        // once per sample.
+
+        let lowpassed: Box<dyn Iterator<Item = Sample<Amplitude>>> = if self.lpf_freq < 1.0 {
+            let coefficient = self.lpf_freq.powi(3) * 0.1;
+            let damping =
+                (5.0 / (1.0 + self.lpf_resonance.powi(2) * 20.0) * (0.01 + coefficient)).min(0.8);
+            let cutoff = Sweep::exponential(
+                resonator_frequency(coefficient),
+                (1.0 + self.lpf_ramp * 0.0001).powf(supersample_rate / 2.0),
+                self.sample_rate,
+            )
+            .clamped(0.0, resonator_frequency(0.1));
+            Box::new(phased.resonant_lowpass(
+                cutoff,
+                coefficient.sqrt() / damping,
+                self.sample_rate,
+            ))
+        } else {
+            phased
+        };
+
+        let highpassed: Box<dyn Iterator<Item = Sample<Amplitude>>> =
+            if self.hpf_freq > 0.0 || self.hpf_ramp > 0.0 {
+                let cutoff = Sweep::exponential(
+                    filter_frequency((self.hpf_freq.powi(2) * 0.1).max(0.00001)),
+                    (1.0 + self.hpf_ramp * 0.0003).powf(JSFXR_SAMPLE_RATE),
+                    self.sample_rate,
+                )
+                .clamped(filter_frequency(0.00001), filter_frequency(0.1));
+                Box::new(lowpassed.highpass(cutoff, self.sample_rate))
+            } else {
+                lowpassed
+            };
+
+        let gain = self.sound_vol.exp() - 1.0;
+        Box::new(highpassed.map(move |sample| Sample {
+            data: Amplitude(*sample * gain),
+            ..sample
+        }))
+    }
+}
+
+impl IntoIterator for SfxrParams {
+    type Item = Sample<Amplitude>;
+    type IntoIter = Safeguard<Box<dyn Iterator<Item = Sample<Amplitude>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.render()
+    }
+}
+
+/// A random number in `0.0..range`, jsfxr's `frnd`.
+fn frnd<R: Rng + ?Sized>(rng: &mut R, range: f64) -> f64 {
+    rng.gen::<f64>() * range
+}
+
+/// A random integer in `0..=max`, jsfxr's `rnd`.
+fn rnd<R: Rng + ?Sized>(rng: &mut R, max: u32) -> u32 {
+    rng.gen_range(0..=max)
+}
+
+/// A random number in `from..to`, jsfxr's `rndr`.
+fn rndr<R: Rng + ?Sized>(rng: &mut R, from: f64, to: f64) -> f64 {
+    from + rng.gen::<f64>() * (to - from)
+}
+
+/// The wave type with jsfxr's numbering.
+fn wave_type(index: u32) -> WaveType {
+    match index {
+        0 => WaveType::Square,
+        1 => WaveType::Sawtooth,
+        2 => WaveType::Sine,
+        3 => WaveType::Noise,
+        4 => WaveType::Triangle,
+        5 => WaveType::PinkNoise,
+        6 => WaveType::Tan,
+        7 => WaveType::Whistle,
+        8 => WaveType::Breaker,
+        9 => WaveType::BitNoise,
+        _ => WaveType::BrownNoise,
+    }
+}
+
+/// Converts a jsfxr oscillator period, in supersamples, to Hz.
+fn period_to_frequency(period: f64) -> f64 {
+    JSFXR_SUPERSAMPLING * JSFXR_SAMPLE_RATE / period
+}
+
+/// Converts a jsfxr envelope parameter to seconds.
+fn envelope_time(value: f64) -> f64 {
+    (value.powi(2) * 100000.0).floor() / JSFXR_SAMPLE_RATE
+}
+
+/// Converts the per supersample coefficient of jsfxr's one pole filters to a cutoff in Hz.
+fn filter_frequency(coefficient: f64) -> f64 {
+    coefficient * JSFXR_SUPERSAMPLING * JSFXR_SAMPLE_RATE / std::f64::consts::TAU
+}
+
+/// Converts the per supersample coefficient of jsfxr's two pole low-pass to its natural
+/// frequency in Hz, the coefficient is the square of the angular frequency per supersample.
+fn resonator_frequency(coefficient: f64) -> f64 {
+    filter_frequency(coefficient.sqrt())
+}
@@ -0,0 +1,409 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Error, ErrorKind, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use crate::{
+    filter::safeguard::{Safeguard, SafeguardReport},
+    Amplitude, Sample, Stereo,
+};
+
+/// The encoding used for every sample in the file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// 8-bit unsigned PCM
+    U8,
+    /// 16-bit signed PCM
+    I16,
+    /// 24-bit signed PCM
+    I24,
+    /// 32-bit IEEE float
+    F32,
+}
+
+impl SampleFormat {
+    /// Float files are not PCM, they need a `cbSize` in the fmt chunk and a fact chunk.
+    fn is_pcm(self) -> bool {
+        self != SampleFormat::F32
+    }
+
+    fn bytes(self) -> u16 {
+        match self {
+            SampleFormat::U8 => 1,
+            SampleFormat::I16 => 2,
+            SampleFormat::I24 => 3,
+            SampleFormat::F32 => 4,
+        }
+    }
+
+    fn encode(self, value: f64, buffer: &mut Vec<u8>) {
+        let value = if !value.is_finite() {
+            0.0
+        } else {
+            value.clamp(-1.0, 1.0)
+        };
+        match self {
+            SampleFormat::U8 => buffer.push(((value + 1.0) * 127.5).round() as u8),
+            SampleFormat::I16 => {
+                buffer.extend_from_slice(&((value * i16::MAX as f64).round() as i16).to_le_bytes())
+            }
+            SampleFormat::I24 => {
+                buffer.extend_from_slice(&((value * 8388607.0).round() as i32).to_le_bytes()[..3])
+            }
+            SampleFormat::F32 => buffer.extend_from_slice(&(value as f32).to_le_bytes()),
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f64 {
+        match self {
+            SampleFormat::U8 => bytes[0] as f64 / 127.5 - 1.0,
+            SampleFormat::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64 / i16::MAX as f64,
+            SampleFormat::I24 => {
+                // Shift into the top of an i32 to sign extend it.
+                (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f64 / 8388607.0
+            }
+            SampleFormat::F32 => {
+                f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64
+            }
+        }
+    }
+}
+
+/// Everything needed to write the header of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WavSpec {
+    pub sample_rate: u32,
+    pub format: SampleFormat,
+}
+
+/// One sample for every channel of the file.
+pub trait Frame {
+    const CHANNELS: u16;
+
+    /// Appends the value of every channel to `values`.
+    fn channels(&self, values: &mut Vec<f64>);
+}
+
+impl Frame for Sample<Amplitude> {
+    const CHANNELS: u16 = 1;
+
+    fn channels(&self, values: &mut Vec<f64>) {
+        values.push(**self);
+    }
+}
+
+impl Frame for Sample<Stereo> {
+    const CHANNELS: u16 = 2;
+
+    fn channels(&self, values: &mut Vec<f64>) {
+        values.push(self.data.left);
+        values.push(self.data.right);
+    }
+}
+
+/// A left and a right sample, e.g. two mono streams zipped together.
+impl Frame for (Sample<Amplitude>, Sample<Amplitude>) {
+    const CHANNELS: u16 = 2;
+
+    fn channels(&self, values: &mut Vec<f64>) {
+        values.push(*self.0);
+        values.push(*self.1);
+    }
+}
+
+/// Streams frames of type `F` into a RIFF/WAVE file, the sizes in the header are patched by
+/// `finish()`.
+///
+/// Every channel passes through a [`Safeguard`] before it's encoded, what it corrected is in
+/// [`WavWriter::report`].
+pub struct WavWriter<W: Write + Seek, F: Frame> {
+    sink: W,
+    spec: WavSpec,
+    start: u64,
+    data_len: u64,
+    safeguard: Safeguard<()>,
+    frames: u64,
+    values: Vec<f64>,
+    buffer: Vec<u8>,
+    frame: PhantomData<F>,
+}
+
+impl<W: Write + Seek, F: Frame> WavWriter<W, F> {
+    /// Writes the header for a file with a channel for every channel of `F`, with the sizes left
+    /// empty.
+    pub fn new(mut sink: W, spec: WavSpec) -> std::io::Result<Self> {
+        let start = sink.stream_position()?;
+        sink.write_all(&header(spec, F::CHANNELS, 0)?)?;
+        Ok(WavWriter {
+            sink,
+            spec,
+            start,
+            data_len: 0,
+            safeguard: Safeguard::new((), spec.sample_rate as u64),
+            frames: 0,
+            values: Vec::new(),
+            buffer: Vec::new(),
+            frame: PhantomData,
+        })
+    }
+
+    /// Fails with [`ErrorKind::InvalidInput`], without writing anything, once the file would
+    /// grow past the 4 GiB a WAV file can describe.
+    pub fn write_frame(&mut self, frame: &F) -> std::io::Result<()> {
+        riff_len(
+            self.spec.format,
+            self.data_len + F::CHANNELS as u64 * self.spec.format.bytes() as u64,
+        )?;
+        self.values.clear();
+        self.buffer.clear();
+        frame.channels(&mut self.values);
+        for (channel, value) in self.values.iter().enumerate() {
+            let value = self.safeguard.guard(channel, *value, self.frames);
+            self.spec.format.encode(value, &mut self.buffer);
+        }
+        self.sink.write_all(&self.buffer)?;
+        self.data_len += self.buffer.len() as u64;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// What the safeguard corrected in the frames written so far.
+    pub fn report(&self) -> SafeguardReport {
+        self.safeguard.report()
+    }
+
+    /// Pads the data chunk, fills in the sizes in the header and returns the sink.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if self.data_len % 2 == 1 {
+            self.sink.write_all(&[0])?;
+        }
+        let end = self.sink.stream_position()?;
+        self.sink.seek(SeekFrom::Start(self.start))?;
+        self.sink
+            .write_all(&header(self.spec, F::CHANNELS, self.data_len)?)?;
+        self.sink.seek(SeekFrom::Start(end))?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+}
+
+/// Writes the whole stream to `sink`, through a [`Safeguard`], and returns what it corrected.
+///
+/// `sink` can't be seeked, so the encoded samples are held in memory until the stream ends and
+/// the header can be written. Fails with [`ErrorKind::InvalidInput`] if the samples don't fit in
+/// the 4 GiB a WAV file can describe.
+pub fn write<W, I>(mut sink: W, spec: WavSpec, frames: I) -> std::io::Result<SafeguardReport>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: Frame,
+{
+    let mut safeguard = Safeguard::new((), spec.sample_rate as u64);
+    let mut values = Vec::new();
+    let mut data = Vec::new();
+    for (phase, frame) in frames.into_iter().enumerate() {
+        values.clear();
+        frame.channels(&mut values);
+        for (channel, value) in values.iter().enumerate() {
+            let value = safeguard.guard(channel, *value, phase as u64);
+            spec.format.encode(value, &mut data);
+        }
+    }
+    let header = header(spec, <I::Item as Frame>::CHANNELS, data.len() as u64)?;
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+    sink.write_all(&header)?;
+    sink.write_all(&data)?;
+    sink.flush()?;
+    Ok(safeguard.report())
+}
+
+/// Writes the whole stream to a new file at `path`, through a [`Safeguard`], and returns what it
+/// corrected.
+pub fn save<P, I>(path: P, spec: WavSpec, frames: I) -> std::io::Result<SafeguardReport>
+where
+    P: AsRef<Path>,
+    I: IntoIterator,
+    I::Item: Frame,
+{
+    let mut writer = WavWriter::<_, I::Item>::new(BufWriter::new(File::create(path)?), spec)?;
+    for frame in frames {
+        writer.write_frame(&frame)?;
+    }
+    let report = writer.report();
+    writer.finish()?;
+    Ok(report)
+}
+
+/// The decoded contents of a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wav {
+    pub spec: WavSpec,
+    pub channels: u16,
+    /// Every channel of every frame in turn, from `-1.0` to `1.0`
+    pub samples: Vec<f64>,
+}
+
+impl Wav {
+    /// Every sample of channel `channel`.
+    pub fn channel(&self, channel: u16) -> Vec<f64> {
+        self.samples
+            .iter()
+            .skip(channel as usize)
+            .step_by(self.channels.max(1) as usize)
+            .copied()
+            .collect()
+    }
+}
+
+/// Reads a PCM or float file in any of the [`SampleFormat`]s from `source`.
+///
+/// A data chunk with a size of `0xFFFFFFFF`, which streaming writers use when they don't know
+/// the size yet, runs to the end of `source`.
+pub fn read<R: Read>(mut source: R) -> std::io::Result<Wav> {
+    let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+
+    let mut riff = [0; 12];
+    source.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(invalid("not a RIFF/WAVE file"));
+    }
+
+    let mut format = None;
+    loop {
+        let mut chunk = [0; 8];
+        source.read_exact(&mut chunk)?;
+        let len = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+        // The length comes from the file, so nothing is allocated up front for it, the body only
+        // grows as far as the file really goes.
+        let mut body = Vec::new();
+        if &chunk[0..4] == b"data" && len == u32::MAX as u64 {
+            source.read_to_end(&mut body)?;
+            return samples(format, body);
+        }
+        let read = if matches!(&chunk[0..4], b"fmt " | b"data") {
+            (&mut source).take(len).read_to_end(&mut body)? as u64
+        } else {
+            io::copy(&mut (&mut source).take(len), &mut io::sink())?
+        };
+        if read < len {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        if len % 2 == 1 && &chunk[0..4] != b"data" {
+            source.read_exact(&mut [0])?;
+        }
+
+        match &chunk[0..4] {
+            b"fmt " => {
+                if body.len() < 16 {
+                    return Err(invalid("fmt chunk too short"));
+                }
+                let field = |at: usize| u16::from_le_bytes([body[at], body[at + 1]]);
+                let mut format_tag = field(0);
+                // WAVE_FORMAT_EXTENSIBLE keeps the real tag at the start of its sub format.
+                if format_tag == 0xFFFE && body.len() >= 26 {
+                    format_tag = field(24);
+                }
+                let sample_format = match (format_tag, field(14)) {
+                    (1, 8) => SampleFormat::U8,
+                    (1, 16) => SampleFormat::I16,
+                    (1, 24) => SampleFormat::I24,
+                    (3, 32) => SampleFormat::F32,
+                    _ => return Err(invalid("unsupported sample format")),
+                };
+                let channels = field(2);
+                if channels == 0 {
+                    return Err(invalid("no channels"));
+                }
+                let sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                format = Some((
+                    WavSpec {
+                        sample_rate,
+                        format: sample_format,
+                    },
+                    channels,
+                ));
+            }
+            b"data" => return samples(format, body),
+            _ => {}
+        }
+    }
+}
+
+/// Decodes the body of the data chunk, in the format of the fmt chunk before it.
+fn samples(format: Option<(WavSpec, u16)>, body: Vec<u8>) -> std::io::Result<Wav> {
+    let (spec, channels) = format
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "data before fmt chunk".to_string()))?;
+    let samples = body
+        .chunks_exact(spec.format.bytes() as usize)
+        .map(|bytes| spec.format.decode(bytes))
+        .collect();
+    Ok(Wav {
+        spec,
+        channels,
+        samples,
+    })
+}
+
+/// Reads the file at `path`.
+pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Wav> {
+    read(BufReader::new(File::open(path)?))
+}
+
+/// The length of the header in front of the samples, 44 bytes for PCM and 58 bytes for float.
+fn header_len(format: SampleFormat) -> u64 {
+    if format.is_pcm() {
+        44
+    } else {
+        58
+    }
+}
+
+/// The size the RIFF chunk has with `data_len` bytes of samples, which has to fit in 32 bits.
+fn riff_len(format: SampleFormat, data_len: u64) -> std::io::Result<u32> {
+    u32::try_from(header_len(format) - 8 + data_len + data_len % 2).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "too many samples for a WAV file".to_string(),
+        )
+    })
+}
+
+/// The header of a PCM or float file with `data_len` bytes of samples.
+fn header(spec: WavSpec, channels: u16, data_len: u64) -> std::io::Result<Vec<u8>> {
+    let riff_len = riff_len(spec.format, data_len)?;
+    let block_align = channels
+        .checked_mul(spec.format.bytes())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "too many channels".to_string()))?;
+    let byte_rate = spec
+        .sample_rate
+        .checked_mul(block_align as u32)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "sample rate too high".to_string()))?;
+    let pcm = spec.format.is_pcm();
+    let mut header = Vec::with_capacity(header_len(spec.format) as usize);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_len.to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&(if pcm { 16u32 } else { 18 }).to_le_bytes());
+    header.extend_from_slice(&(if pcm { 1u16 } else { 3 }).to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&spec.sample_rate.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&(spec.format.bytes() * 8).to_le_bytes());
+    // Sizes below fit, since they're smaller than the RIFF chunk.
+    if !pcm {
+        // No extra format bytes, then the number of frames.
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(b"fact");
+        header.extend_from_slice(&4u32.to_le_bytes());
+        header.extend_from_slice(&((data_len / block_align.max(1) as u64) as u32).to_le_bytes());
+    }
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(data_len as u32).to_le_bytes());
+    Ok(header)
+}
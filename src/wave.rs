@@ -1,104 +1,898 @@
 use std::io::{Seek, SeekFrom};
 
-use crate::{Amplitude, Frequency, Sample};
+use crate::{generator::noise, modulation::Modulation, Amplitude, Frequency, Sample};
+
+/// The duty cycle is clamped to `MIN_DUTY_CYCLE..=1.0`, below that the wave is mostly silence.
+const MIN_DUTY_CYCLE: f64 = 0.01;
+
+/// Number of rows summed up by [`PinkNoiseGenerator`].
+const PINK_NOISE_ROWS: u32 = 8;
+
+/// How much of the previous value [`BrownNoiseGenerator`] keeps on every step.
+const BROWN_NOISE_LEAK: f64 = 0.99;
+
+/// Number of steps [`BrownNoiseGenerator`] integrates to rebuild its value after a seek, older
+/// steps have decayed below 0.01%.
+const BROWN_NOISE_MEMORY: u32 = 1000;
+
+/// The state [`BitNoiseGenerator`] starts from.
+const BIT_NOISE_START: u16 = 1 << 14;
+
+/// Number of steps after which [`BitNoiseGenerator`] repeats itself.
+const BIT_NOISE_PERIOD: i64 = (1 << 15) - 1;
+
+/// How an oscillator keeps harmonics above the Nyquist frequency from folding back into the
+/// audible range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AntiAliasing {
+    /// Samples the ideal wave directly.
+    #[default]
+    None,
+    /// Smooths every jump in the wave with a polynomial band-limited step (PolyBLEP).
+    PolyBlep,
+    /// Samples the wave this many times per sample and averages them, jsfxr uses 8.
+    Supersample(u32),
+}
 
 /// Generates a square wave based on the frequency from recieved from generator.
+///
+/// The wave is `1.0` for the duty cycle and `-1.0` for the rest of the period. Up to 0.2.3 it
+/// switched between `0.0` and `1.0`, i.e. half as loud and with a DC offset.
 #[derive(Clone, Copy)]
-pub struct SquareWaveGenerator<T> {
+pub struct SquareWaveGenerator<T, D = f64> {
     pub generator: T,
-    pub duty_cycle: f64,
+    pub duty_cycle: D,
     pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
 }
 
 /// Generates a sine wave based on the frequency from recieved from generator.
 #[derive(Clone, Copy)]
-pub struct SineWaveGenerator<T> {
+pub struct SineWaveGenerator<T, D = f64> {
     pub generator: T,
-    pub duty_cycle: f64,
+    pub duty_cycle: D,
     pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
 }
 
 /// Generates a sawtooth wave based on the frequency from recieved from generator.
+///
+/// The ramp rises from `-1.0` to `1.0` over the duty cycle and rests at `0.0` for the rest of the
+/// period. Up to 0.2.3 it rose from `0.0` to `1.0`, i.e. half as loud and with a DC offset.
+#[derive(Clone, Copy)]
+pub struct SawtoothWaveGenerator<T, D = f64> {
+    pub generator: T,
+    pub duty_cycle: D,
+    pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
+}
+
+/// Generates a triangle wave based on the frequency from recieved from generator.
+#[derive(Clone, Copy)]
+pub struct TriangleWaveGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
+}
+
+/// Generates bfxr's breaker wave, a wave made of parabolas, based on the frequency from
+/// recieved from generator.
+#[derive(Clone, Copy)]
+pub struct BreakerWaveGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
+}
+
+/// Generates a tangent wave, clipped to `-1.0..=1.0`, based on the frequency from recieved from
+/// generator.
+#[derive(Clone, Copy)]
+pub struct TanWaveGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
+}
+
+/// Generates a sine wave with a quieter 20th harmonic on top, based on the frequency from
+/// recieved from generator.
 #[derive(Clone, Copy)]
-pub struct SawtoothWaveGenerator<T> {
+pub struct WhistleWaveGenerator<T> {
     pub generator: T,
-    pub duty_cycle: f64,
     pub sample_rate: u64,
+    pub anti_aliasing: AntiAliasing,
+    phasor: Phasor,
+}
+
+/// Generates white noise, picking a new value once per period of the frequency recieved from
+/// generator, so the noise follows slides and arpeggios like a tone does.
+#[derive(Clone, Copy)]
+pub struct NoiseWaveGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub seed: u64,
+    pub values_per_period: u32,
+    phasor: Phasor,
+}
+
+/// Generates pink noise, picking a new value once per period of the frequency recieved from
+/// generator.
+#[derive(Clone, Copy)]
+pub struct PinkNoiseGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub seed: u64,
+    pub values_per_period: u32,
+    phasor: Phasor,
+}
+
+/// Generates brown noise, picking a new value once per period of the frequency recieved from
+/// generator.
+#[derive(Clone, Copy)]
+pub struct BrownNoiseGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub seed: u64,
+    pub values_per_period: u32,
+    phasor: Phasor,
+    value: f64,
+    value_cycle: i64,
+}
+
+/// Generates the 1-bit noise of a 15-bit linear feedback shift register, like the NES noise
+/// channel, shifting once per period of the frequency recieved from generator.
+#[derive(Clone, Copy)]
+pub struct BitNoiseGenerator<T> {
+    pub generator: T,
+    pub sample_rate: u64,
+    pub values_per_period: u32,
+    phasor: Phasor,
+    register: u16,
+    register_cycle: i64,
+}
+
+impl<T, D> SquareWaveGenerator<T, D> {
+    pub fn new(generator: T, sample_rate: u64, duty_cycle: D) -> Self {
+        SquareWaveGenerator {
+            generator,
+            duty_cycle,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T, D> SineWaveGenerator<T, D> {
+    pub fn new(generator: T, sample_rate: u64, duty_cycle: D) -> Self {
+        SineWaveGenerator {
+            generator,
+            duty_cycle,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T, D> SawtoothWaveGenerator<T, D> {
+    pub fn new(generator: T, sample_rate: u64, duty_cycle: D) -> Self {
+        SawtoothWaveGenerator {
+            generator,
+            duty_cycle,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T> TriangleWaveGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64) -> Self {
+        TriangleWaveGenerator {
+            generator,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T> BreakerWaveGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64) -> Self {
+        BreakerWaveGenerator {
+            generator,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T> TanWaveGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64) -> Self {
+        TanWaveGenerator {
+            generator,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T> WhistleWaveGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64) -> Self {
+        WhistleWaveGenerator {
+            generator,
+            sample_rate,
+            anti_aliasing: AntiAliasing::None,
+            phasor: Phasor::default(),
+        }
+    }
+
+    pub fn anti_aliased(self, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            anti_aliasing,
+            ..self
+        }
+    }
+}
+
+impl<T> NoiseWaveGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64, seed: u64) -> Self {
+        NoiseWaveGenerator {
+            generator,
+            sample_rate,
+            seed,
+            values_per_period: 1,
+            phasor: Phasor::default(),
+        }
+    }
+
+    /// Picks `values_per_period` new values per period instead of one, jsfxr picks 32.
+    pub fn values_per_period(self, values_per_period: u32) -> Self {
+        Self {
+            values_per_period: values_per_period.max(1),
+            ..self
+        }
+    }
+
+    fn value(&self, cycle: i64) -> f64 {
+        noise(self.seed, cycle as u64) * 2.0 - 1.0
+    }
+}
+
+impl<T> PinkNoiseGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64, seed: u64) -> Self {
+        PinkNoiseGenerator {
+            generator,
+            sample_rate,
+            seed,
+            values_per_period: 1,
+            phasor: Phasor::default(),
+        }
+    }
+
+    /// Picks `values_per_period` new values per period instead of one, jsfxr picks 32.
+    pub fn values_per_period(self, values_per_period: u32) -> Self {
+        Self {
+            values_per_period: values_per_period.max(1),
+            ..self
+        }
+    }
+
+    /// Voss-McCartney: row `k` only changes every `2^k` periods, which are added up with a
+    /// white row that changes every period.
+    fn value(&self, cycle: i64) -> f64 {
+        let cycle = cycle as u64;
+        let rows = (0..PINK_NOISE_ROWS)
+            .map(|row| noise(row_seed(self.seed, row), cycle >> row) * 2.0 - 1.0)
+            .sum::<f64>();
+        ((rows + noise(self.seed, cycle) * 2.0 - 1.0) / 4.0).clamp(-1.0, 1.0)
+    }
+}
+
+impl<T> BrownNoiseGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64, seed: u64) -> Self {
+        let mut generator = BrownNoiseGenerator {
+            generator,
+            sample_rate,
+            seed,
+            values_per_period: 1,
+            phasor: Phasor::default(),
+            value: 0.0,
+            value_cycle: 0,
+        };
+        generator.value = generator.integrate(0);
+        generator
+    }
+
+    /// Picks `values_per_period` new values per period instead of one, jsfxr picks 32.
+    pub fn values_per_period(self, values_per_period: u32) -> Self {
+        Self {
+            values_per_period: values_per_period.max(1),
+            ..self
+        }
+    }
+
+    /// Leaky integration of the last `BROWN_NOISE_MEMORY` random steps before `cycle`.
+    fn integrate(&self, cycle: i64) -> f64 {
+        (0..BROWN_NOISE_MEMORY)
+            .map(|age| {
+                BROWN_NOISE_LEAK.powi(age as i32) * self.step(cycle.wrapping_sub(age as i64))
+            })
+            .sum()
+    }
+
+    fn step(&self, cycle: i64) -> f64 {
+        (noise(self.seed, cycle as u64) * 2.0 - 1.0) * 0.1
+    }
+
+    /// Takes a single leaky step for every new cycle. Cycles skipped over at high frequencies
+    /// take no step of their own.
+    fn value(&mut self, cycle: i64) -> f64 {
+        if cycle != self.value_cycle {
+            self.value = BROWN_NOISE_LEAK * self.value + self.step(cycle);
+            self.value_cycle = cycle;
+        }
+        self.value.clamp(-1.0, 1.0)
+    }
+}
+
+impl<T> BitNoiseGenerator<T> {
+    pub fn new(generator: T, sample_rate: u64) -> Self {
+        BitNoiseGenerator {
+            generator,
+            sample_rate,
+            values_per_period: 1,
+            phasor: Phasor::default(),
+            register: BIT_NOISE_START,
+            register_cycle: 0,
+        }
+    }
+
+    /// Picks `values_per_period` new values per period instead of one, jsfxr picks 32.
+    pub fn values_per_period(self, values_per_period: u32) -> Self {
+        Self {
+            values_per_period: values_per_period.max(1),
+            ..self
+        }
+    }
+
+    fn value(&mut self, cycle: i64) -> f64 {
+        let (mut register, steps) = if cycle >= self.register_cycle {
+            (self.register, cycle.wrapping_sub(self.register_cycle))
+        } else {
+            // The register repeats itself, so it can always be wound forward from the start.
+            (BIT_NOISE_START, cycle.rem_euclid(BIT_NOISE_PERIOD))
+        };
+        for _ in 0..steps.rem_euclid(BIT_NOISE_PERIOD) {
+            let feedback = (register ^ (register >> 1)) & 1;
+            register = (register >> 1) | (feedback << 14);
+        }
+        self.register = register;
+        self.register_cycle = cycle;
+        if register & 1 == 0 {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>, D: Modulation> Iterator for SquareWaveGenerator<T, D> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let duty_cycle = self.duty_cycle.next_value().clamp(MIN_DUTY_CYCLE, 1.0);
+            Sample {
+                data: Amplitude(self.phasor.sample(
+                    *sample,
+                    self.sample_rate,
+                    self.anti_aliasing,
+                    |phase| if phase < duty_cycle { 1.0 } else { -1.0 },
+                    &[(0.0, 2.0), (duty_cycle, -2.0)],
+                )),
+                phase: sample.phase,
+            }
+        })
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>, D: Modulation> Iterator for SineWaveGenerator<T, D> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let duty_cycle = self.duty_cycle.next_value().clamp(MIN_DUTY_CYCLE, 1.0);
+            Sample {
+                data: Amplitude(self.phasor.sample(
+                    *sample,
+                    self.sample_rate,
+                    self.anti_aliasing,
+                    |phase| {
+                        if phase < duty_cycle {
+                            (core::f64::consts::TAU * phase / duty_cycle).sin()
+                        } else {
+                            0.0
+                        }
+                    },
+                    &[],
+                )),
+                phase: sample.phase,
+            }
+        })
+    }
 }
 
+impl<T: Iterator<Item = Sample<Frequency>>, D: Modulation> Iterator
+    for SawtoothWaveGenerator<T, D>
+{
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let duty_cycle = self.duty_cycle.next_value().clamp(MIN_DUTY_CYCLE, 1.0);
+            // The ramp either jumps straight back down, or rests at zero after the duty cycle.
+            let steps = if duty_cycle < 1.0 {
+                [(0.0, -1.0), (duty_cycle, -1.0)]
+            } else {
+                [(0.0, -2.0), (duty_cycle, 0.0)]
+            };
+            Sample {
+                data: Amplitude(self.phasor.sample(
+                    *sample,
+                    self.sample_rate,
+                    self.anti_aliasing,
+                    |phase| {
+                        if phase < duty_cycle {
+                            2.0 * phase / duty_cycle - 1.0
+                        } else {
+                            0.0
+                        }
+                    },
+                    &steps,
+                )),
+                phase: sample.phase,
+            }
+        })
+    }
+}
 
-impl<T: Iterator<Item = Sample<Frequency>>> Iterator for SquareWaveGenerator<T> {
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for TriangleWaveGenerator<T> {
     type Item = Sample<Amplitude>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.generator.next().map(|sample| Sample {
-            data: Amplitude(
-                (*sample * sample.phase as f64 / self.sample_rate as f64 - self.duty_cycle)
-                    .fract()
-                    .round(),
-            ),
+            data: Amplitude(self.phasor.sample(
+                *sample,
+                self.sample_rate,
+                self.anti_aliasing,
+                |phase| 1.0 - 4.0 * (phase - 0.5).abs(),
+                &[],
+            )),
             phase: sample.phase,
         })
     }
 }
 
-impl<T: Iterator<Item = Sample<Frequency>>> Iterator for SineWaveGenerator<T> {
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for BreakerWaveGenerator<T> {
     type Item = Sample<Amplitude>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.generator.next().map(|sample| Sample {
-            data: Amplitude(
-                if (*sample * sample.phase as f64 / self.sample_rate as f64).fract()
-                    < self.duty_cycle
-                {
-                    (*sample * core::f64::consts::TAU * sample.phase as f64
-                        / (self.sample_rate as f64 * self.duty_cycle))
-                        .sin()
-                } else {
-                    0.0
+            data: Amplitude(self.phasor.sample(
+                *sample,
+                self.sample_rate,
+                self.anti_aliasing,
+                |phase| {
+                    let phase = (phase + 0.25).fract();
+                    2.0 * (1.0 - 2.0 * phase * phase).abs() - 1.0
                 },
-            ),
+                &[],
+            )),
             phase: sample.phase,
         })
     }
 }
 
-impl<T: Iterator<Item = Sample<Frequency>>> Iterator for SawtoothWaveGenerator<T> {
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for TanWaveGenerator<T> {
     type Item = Sample<Amplitude>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.generator.next().map(|sample| Sample {
-            data: Amplitude(
-                if (*sample * sample.phase as f64 / self.sample_rate as f64).fract()
-                    < self.duty_cycle
-                {
-                    (*sample * sample.phase as f64 / (self.sample_rate as f64 * self.duty_cycle))
-                        .fract()
-                } else {
-                    0.0
+            data: Amplitude(self.phasor.sample(
+                *sample,
+                self.sample_rate,
+                self.anti_aliasing,
+                |phase| (core::f64::consts::PI * phase).tan().clamp(-1.0, 1.0),
+                &[(0.5, -2.0)],
+            )),
+            phase: sample.phase,
+        })
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for WhistleWaveGenerator<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| Sample {
+            data: Amplitude(self.phasor.sample(
+                *sample,
+                self.sample_rate,
+                self.anti_aliasing,
+                |phase| {
+                    0.75 * (core::f64::consts::TAU * phase).sin()
+                        + 0.25 * (core::f64::consts::TAU * 20.0 * phase).sin()
                 },
-            ),
+                &[],
+            )),
             phase: sample.phase,
         })
     }
 }
 
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for NoiseWaveGenerator<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let cycle = self.phasor.cycle;
+            self.phasor
+                .advance(*sample * self.values_per_period as f64, self.sample_rate);
+            Sample {
+                data: Amplitude(self.value(cycle)),
+                phase: sample.phase,
+            }
+        })
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for PinkNoiseGenerator<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let cycle = self.phasor.cycle;
+            self.phasor
+                .advance(*sample * self.values_per_period as f64, self.sample_rate);
+            Sample {
+                data: Amplitude(self.value(cycle)),
+                phase: sample.phase,
+            }
+        })
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for BrownNoiseGenerator<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let cycle = self.phasor.cycle;
+            self.phasor
+                .advance(*sample * self.values_per_period as f64, self.sample_rate);
+            Sample {
+                data: Amplitude(self.value(cycle)),
+                phase: sample.phase,
+            }
+        })
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>>> Iterator for BitNoiseGenerator<T> {
+    type Item = Sample<Amplitude>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.generator.next().map(|sample| {
+            let cycle = self.phasor.cycle;
+            self.phasor
+                .advance(*sample * self.values_per_period as f64, self.sample_rate);
+            Sample {
+                data: Amplitude(self.value(cycle)),
+                phase: sample.phase,
+            }
+        })
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek, D: Modulation> Seek
+    for SquareWaveGenerator<T, D>
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self
+            .phasor
+            .seek(&mut self.generator, self.sample_rate, pos)?;
+        self.duty_cycle.seek_to(position);
+        Ok(position)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek, D: Modulation> Seek for SineWaveGenerator<T, D> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self
+            .phasor
+            .seek(&mut self.generator, self.sample_rate, pos)?;
+        self.duty_cycle.seek_to(position);
+        Ok(position)
+    }
+}
 
-impl<T: Seek> Seek for SquareWaveGenerator<T> {
+impl<T: Iterator<Item = Sample<Frequency>> + Seek, D: Modulation> Seek
+    for SawtoothWaveGenerator<T, D>
+{
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
-        self.generator.seek(pos)
+        let position = self
+            .phasor
+            .seek(&mut self.generator, self.sample_rate, pos)?;
+        self.duty_cycle.seek_to(position);
+        Ok(position)
     }
 }
 
-impl<T: Seek> Seek for SineWaveGenerator<T> {
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for TriangleWaveGenerator<T> {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
-        self.generator.seek(pos)
+        self.phasor.seek(&mut self.generator, self.sample_rate, pos)
     }
 }
 
-impl<T: Seek> Seek for SawtoothWaveGenerator<T> {
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for BreakerWaveGenerator<T> {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
-        self.generator.seek(pos)
+        self.phasor.seek(&mut self.generator, self.sample_rate, pos)
     }
 }
 
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for TanWaveGenerator<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.phasor.seek(&mut self.generator, self.sample_rate, pos)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for WhistleWaveGenerator<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.phasor.seek(&mut self.generator, self.sample_rate, pos)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for NoiseWaveGenerator<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.phasor.seek_scaled(
+            &mut self.generator,
+            self.sample_rate,
+            self.values_per_period as f64,
+            pos,
+        )
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for PinkNoiseGenerator<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.phasor.seek_scaled(
+            &mut self.generator,
+            self.sample_rate,
+            self.values_per_period as f64,
+            pos,
+        )
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for BrownNoiseGenerator<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = self.phasor.seek_scaled(
+            &mut self.generator,
+            self.sample_rate,
+            self.values_per_period as f64,
+            pos,
+        )?;
+        // The integrator carries the history, rebuild it for the new cycle.
+        self.value_cycle = self.phasor.cycle;
+        self.value = self.integrate(self.value_cycle);
+        Ok(position)
+    }
+}
+
+impl<T: Iterator<Item = Sample<Frequency>> + Seek> Seek for BitNoiseGenerator<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, std::io::Error> {
+        self.phasor.seek_scaled(
+            &mut self.generator,
+            self.sample_rate,
+            self.values_per_period as f64,
+            pos,
+        )
+    }
+}
+
+/// Integrates the frequency of every sample into the position within the current period, so the
+/// wave stays continuous when the frequency changes.
+#[derive(Clone, Copy, Default)]
+struct Phasor {
+    /// Position within the period, in `0.0..1.0`
+    phase: f64,
+    /// Number of whole periods completed
+    cycle: i64,
+    /// Number of samples integrated so far
+    position: u64,
+}
+
+impl Phasor {
+    /// Returns the phase for the current sample and moves on by one sample of `frequency`.
+    fn advance(&mut self, frequency: f64, sample_rate: u64) -> f64 {
+        let phase = self.phase;
+        self.add(frequency / sample_rate as f64);
+        self.position += 1;
+        phase
+    }
+
+    fn add(&mut self, cycles: f64) {
+        let total = self.phase + cycles;
+        if !total.is_finite() {
+            return;
+        }
+        let whole = total.floor();
+        // Only the noise generators count cycles, and they only need them to tell cycles apart,
+        // so the count wraps instead of overflowing on absurd frequencies.
+        self.cycle = self.cycle.wrapping_add(whole as i64);
+        self.phase = total - whole;
+        // Rounding can land exactly on the end of the period.
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+            self.cycle += 1;
+        }
+    }
+
+    /// Moves on by one sample of `frequency` and returns the value of `wave` for it.
+    ///
+    /// `steps` lists the position and height of every jump in `wave`, which is where PolyBLEP
+    /// smooths it.
+    fn sample<F>(
+        &mut self,
+        frequency: f64,
+        sample_rate: u64,
+        anti_aliasing: AntiAliasing,
+        wave: F,
+        steps: &[(f64, f64)],
+    ) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        let increment = frequency / sample_rate as f64;
+        let phase = self.advance(frequency, sample_rate);
+        match anti_aliasing {
+            AntiAliasing::None => wave(phase),
+            AntiAliasing::PolyBlep => {
+                let increment = increment.abs().min(0.5);
+                wave(phase)
+                    + steps
+                        .iter()
+                        .map(|(at, height)| {
+                            height / 2.0 * poly_blep((phase - at).rem_euclid(1.0), increment)
+                        })
+                        .sum::<f64>()
+            }
+            AntiAliasing::Supersample(factor) => {
+                let factor = factor.max(1);
+                (0..factor)
+                    .map(|i| wave((phase + increment * i as f64 / factor as f64).rem_euclid(1.0)))
+                    .sum::<f64>()
+                    / factor as f64
+            }
+        }
+    }
+
+    /// Seeks `generator` and integrates the frequencies between the old and the new position, in
+    /// either direction.
+    ///
+    /// This is O(n) in the distance seeked, every frequency in between is read from `generator`.
+    fn seek<T>(
+        &mut self,
+        generator: &mut T,
+        sample_rate: u64,
+        pos: SeekFrom,
+    ) -> std::io::Result<u64>
+    where
+        T: Iterator<Item = Sample<Frequency>> + Seek,
+    {
+        self.seek_scaled(generator, sample_rate, 1.0, pos)
+    }
+
+    /// Like [`Phasor::seek`], for a phasor advanced by `scale` times the frequency of `generator`.
+    fn seek_scaled<T>(
+        &mut self,
+        generator: &mut T,
+        sample_rate: u64,
+        scale: f64,
+        pos: SeekFrom,
+    ) -> std::io::Result<u64>
+    where
+        T: Iterator<Item = Sample<Frequency>> + Seek,
+    {
+        let target = generator.seek(pos)?;
+        let (from, to) = if target < self.position {
+            (target, self.position)
+        } else {
+            (self.position, target)
+        };
+        generator.seek(SeekFrom::Start(from))?;
+        let cycles = generator
+            .take((to - from) as usize)
+            .map(|sample| *sample * scale / sample_rate as f64)
+            .sum::<f64>();
+        self.add(if target < self.position {
+            -cycles
+        } else {
+            cycles
+        });
+        self.position = target;
+        generator.seek(SeekFrom::Start(target))
+    }
+}
+
+/// The difference between a band-limited and an ideal step of height 2 at `phase` 0, `increment`
+/// being the phase covered by one sample.
+fn poly_blep(phase: f64, increment: f64) -> f64 {
+    if increment <= 0.0 {
+        0.0
+    } else if phase < increment {
+        let t = phase / increment;
+        2.0 * t - t * t - 1.0
+    } else if phase > 1.0 - increment {
+        let t = (phase - 1.0) / increment;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// A seed for every row of [`PinkNoiseGenerator`] that doesn't overlap with the others.
+fn row_seed(seed: u64, row: u32) -> u64 {
+    seed ^ (row as u64 + 1).wrapping_mul(0xD6E8FEB86659FD93)
+}
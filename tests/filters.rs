@@ -0,0 +1,805 @@
+use std::{
+    cell::RefCell,
+    io::{Seek, SeekFrom},
+    rc::Rc,
+};
+
+use rsfxr::{
+    filter::{
+        biquad::{BiquadKind, Coefficients},
+        highpass::Highpass,
+        lowpass::Lowpass,
+        safeguard::{Correction, SafeguardReport},
+        state_variable::StateVariableMode,
+        tremolo::TremoloShape,
+        waveshaper::Shape,
+        AmplitudeDomainFilterable, FrequencyDomainFilterable, StereoFilterable,
+    },
+    generator::ConstantFrequencyGenerator,
+    modulation::{Modulator, Sweep},
+    Amplitude, Sample, Stereo,
+};
+
+const SAMPLE_RATE: u64 = 44100;
+const HALF_POWER_DB: f64 = -3.0103;
+
+fn decibels(gain: f64) -> f64 {
+    20.0 * gain.log10()
+}
+
+fn sine(frequency: f64) -> impl Iterator<Item = Sample<Amplitude>> {
+    (0..SAMPLE_RATE).map(move |phase| Sample {
+        data: Amplitude(
+            (std::f64::consts::TAU * frequency * phase as f64 / SAMPLE_RATE as f64).sin(),
+        ),
+        phase,
+    })
+}
+
+/// Peak amplitude of the second half of the stream, once the filter has settled.
+fn settled_peak(samples: impl Iterator<Item = Sample<Amplitude>>) -> f64 {
+    samples
+        .skip(SAMPLE_RATE as usize / 2)
+        .fold(0.0, |peak, sample| f64::max(peak, sample.abs()))
+}
+
+#[test]
+fn lowpass_is_half_power_at_cutoff() {
+    for cutoff in [50.0, 100.0, 250.0, 500.0] {
+        let filter = Lowpass::new(sine(cutoff), cutoff, SAMPLE_RATE);
+        let response = decibels(filter.magnitude_response(cutoff));
+        assert!(
+            (response - HALF_POWER_DB).abs() < 0.25,
+            "{cutoff} Hz: {response} dB"
+        );
+        let measured = decibels(settled_peak(filter));
+        assert!(
+            (measured - response).abs() < 0.05,
+            "{cutoff} Hz: measured {measured} dB, expected {response} dB"
+        );
+    }
+}
+
+#[test]
+fn highpass_is_half_power_at_cutoff() {
+    for cutoff in [50.0, 100.0, 250.0, 500.0] {
+        let filter = Highpass::new(sine(cutoff), cutoff, SAMPLE_RATE);
+        let response = decibels(filter.magnitude_response(cutoff));
+        assert!(
+            (response - HALF_POWER_DB).abs() < 0.25,
+            "{cutoff} Hz: {response} dB"
+        );
+        let measured = decibels(settled_peak(filter));
+        assert!(
+            (measured - response).abs() < 0.05,
+            "{cutoff} Hz: measured {measured} dB, expected {response} dB"
+        );
+    }
+}
+
+#[test]
+fn lowpass_passes_low_and_stops_high_frequencies() {
+    let filter = Lowpass::new(sine(20.0), 1000.0, SAMPLE_RATE);
+    assert!(filter.magnitude_response(0.0) > 0.9999);
+    assert!(filter.magnitude_response(10000.0) < 0.2);
+    assert!(settled_peak(sine(20.0).lowpass(1000.0, SAMPLE_RATE)) > 0.99);
+    assert!(settled_peak(sine(10000.0).lowpass(1000.0, SAMPLE_RATE)) < 0.2);
+}
+
+#[test]
+fn highpass_passes_high_and_stops_low_frequencies() {
+    let filter = Highpass::new(sine(20.0), 1000.0, SAMPLE_RATE);
+    assert!(filter.magnitude_response(0.0) < 1e-9);
+    assert!(filter.magnitude_response(10000.0) > 0.9);
+    assert!(settled_peak(sine(20.0).highpass(1000.0, SAMPLE_RATE)) < 0.05);
+    assert!(settled_peak(sine(10000.0).highpass(1000.0, SAMPLE_RATE)) > 0.9);
+}
+
+#[test]
+fn biquad_designs_match_their_reference_gains() {
+    let design = |kind, gain| Coefficients::design(kind, 1000.0, 0.707, gain, SAMPLE_RATE);
+    let response = |kind, gain, frequency| {
+        decibels(design(kind, gain).magnitude_response(frequency, SAMPLE_RATE))
+    };
+
+    assert!((response(BiquadKind::Lowpass, 0.0, 1000.0) - HALF_POWER_DB).abs() < 0.01);
+    assert!((response(BiquadKind::Highpass, 0.0, 1000.0) - HALF_POWER_DB).abs() < 0.01);
+    assert!(response(BiquadKind::Bandpass, 0.0, 1000.0).abs() < 0.01);
+    assert!(response(BiquadKind::Notch, 0.0, 1000.0) < -100.0);
+    assert!((response(BiquadKind::Peaking, 6.0, 1000.0) - 6.0).abs() < 0.01);
+    assert!((response(BiquadKind::LowShelf, -6.0, 10.0) + 6.0).abs() < 0.01);
+    assert!(response(BiquadKind::LowShelf, -6.0, 20000.0).abs() < 0.1);
+    assert!((response(BiquadKind::HighShelf, 6.0, 20000.0) - 6.0).abs() < 0.1);
+    assert!(response(BiquadKind::HighShelf, 6.0, 10.0).abs() < 0.01);
+    for frequency in [50.0, 1000.0, 15000.0] {
+        assert!(response(BiquadKind::Allpass, 0.0, frequency).abs() < 0.01);
+    }
+}
+
+#[test]
+fn biquad_output_matches_its_magnitude_response() {
+    let coefficients = Coefficients::design(BiquadKind::Peaking, 500.0, 2.0, 9.0, SAMPLE_RATE);
+    for frequency in [100.0, 500.0, 2000.0] {
+        let measured = settled_peak(sine(frequency).peaking_eq(500.0, 2.0, 9.0, SAMPLE_RATE));
+        let expected = coefficients.magnitude_response(frequency, SAMPLE_RATE);
+        assert!(
+            (decibels(measured) - decibels(expected)).abs() < 0.05,
+            "{frequency} Hz: measured {measured}, expected {expected}"
+        );
+    }
+}
+
+#[test]
+fn state_variable_outputs_at_cutoff() {
+    let filtered = |mode| {
+        settled_peak(
+            sine(1000.0)
+                .state_variable(
+                    ConstantFrequencyGenerator::new(1000.0),
+                    std::f64::consts::FRAC_1_SQRT_2,
+                    SAMPLE_RATE,
+                )
+                .output(mode),
+        )
+    };
+    assert!((decibels(filtered(StateVariableMode::Lowpass)) - HALF_POWER_DB).abs() < 0.05);
+    assert!((decibels(filtered(StateVariableMode::Highpass)) - HALF_POWER_DB).abs() < 0.05);
+    assert!((filtered(StateVariableMode::Bandpass) - std::f64::consts::FRAC_1_SQRT_2).abs() < 0.01);
+    assert!(filtered(StateVariableMode::Notch) < 0.01);
+}
+
+#[test]
+fn state_variable_is_stable_under_fast_cutoff_modulation() {
+    let cutoff = ConstantFrequencyGenerator::new(2000.0).vibrato(200.0, 0.99, SAMPLE_RATE);
+    let peak = settled_peak(
+        sine(2000.0)
+            .state_variable(cutoff, 20.0, SAMPLE_RATE)
+            .output(StateVariableMode::Lowpass),
+    );
+    assert!(peak.is_finite() && peak < 100.0, "{peak}");
+}
+
+#[test]
+fn phaser_adds_the_stream_delayed_by_the_offset() {
+    // 1020 samples.
+    let sample_rate = 8 * 44100;
+    let delay = 1020.0 / sample_rate as f64;
+    let impulse = (0..2100).map(|phase| Sample {
+        data: Amplitude(if phase == 0 { 1.0 } else { 0.0 }),
+        phase,
+    });
+    let output: Vec<f64> = impulse
+        .phaser(delay, 0.0, 2.0 * delay, 0.5, 1.0, sample_rate)
+        .map(|sample| *sample)
+        .collect();
+    assert_eq!(output[0], 1.0);
+    assert!((output[1020] - 1.0).abs() < 1e-9);
+    assert!((output[2040] - 0.5).abs() < 1e-9);
+    assert!(output[1..1020].iter().all(|value| value.abs() < 1e-9));
+    assert!(output[1021..2040].iter().all(|value| value.abs() < 1e-9));
+}
+
+#[test]
+fn phaser_delay_sweeps_up_to_the_max_delay() {
+    // Starts at 10 samples and grows by one sample every 10 samples.
+    let output: Vec<f64> = impulse(1, 2002)
+        .phaser(0.01, 0.1, 0.05, 0.0, 1.0, 1000)
+        .map(|sample| *sample)
+        .collect();
+    // The impulse at sample 1 comes back once the delay has grown to the time since it.
+    let echo = output[2..]
+        .iter()
+        .position(|value| value.abs() > 0.5)
+        .unwrap()
+        + 2;
+    assert!((11..=13).contains(&echo), "{echo}");
+    assert!(output[100..].iter().all(|value| value.abs() < 1e-9));
+
+    let capped: Vec<f64> = impulse(0, 201)
+        .phaser(0.1, 0.0, 0.05, 0.0, 1.0, 1000)
+        .map(|sample| *sample)
+        .collect();
+    assert!((capped[50] - 1.0).abs() < 1e-9);
+    assert!(capped[1..].iter().map(|value| value.abs()).sum::<f64>() < 1.0 + 1e-9);
+}
+
+#[test]
+fn delay_echoes_until_the_tail_dies_out() {
+    let impulse = (0..5).map(|phase| Sample {
+        data: Amplitude(if phase == 0 { 1.0 } else { 0.0 }),
+        phase,
+    });
+    let output: Vec<Sample<Amplitude>> = impulse.delay(0.01, 0.5, 1.0, 1000).collect();
+    for (echo, level) in [(0, 1.0), (10, 1.0), (20, 0.5), (30, 0.25), (40, 0.125)] {
+        assert!(
+            (*output[echo] - level).abs() < 1e-9,
+            "{echo}: {}",
+            *output[echo]
+        );
+    }
+    assert!(output
+        .iter()
+        .enumerate()
+        .all(|(index, sample)| sample.phase == index as u64));
+    let last = output
+        .iter()
+        .rposition(|sample| sample.abs() > 0.0)
+        .unwrap();
+    assert!(output[last].abs() < 0.001);
+    assert!(
+        output.len() < last + 30,
+        "{} samples after the last echo",
+        output.len() - last
+    );
+}
+
+#[test]
+fn reverb_tail_grows_with_the_room_and_ends() {
+    let tail = |room_size| {
+        let impulse = (0..100).map(|phase| Sample {
+            data: Amplitude(if phase == 0 { 1.0 } else { 0.0 }),
+            phase,
+        });
+        let output: Vec<f64> = impulse
+            .reverb(room_size, 0.5, 1.0, 0.3, 0.0, SAMPLE_RATE)
+            .map(|sample| sample.data.left)
+            .collect();
+        assert!(output.iter().all(|value| value.is_finite()));
+        assert!(output.iter().any(|value| value.abs() > 0.001));
+        output.len()
+    };
+    let small = tail(0.2);
+    let large = tail(0.9);
+    assert!(small > SAMPLE_RATE as usize / 10, "{small}");
+    assert!(large > 2 * small, "{small} {large}");
+    assert!(large < 20 * SAMPLE_RATE as usize, "{large}");
+}
+
+#[test]
+fn reverb_width_sets_how_different_the_channels_are() {
+    let difference = |width| {
+        impulse(1, 2)
+            .reverb(0.5, 0.5, width, 0.3, 0.0, SAMPLE_RATE)
+            .map(|sample| (sample.data.left - sample.data.right).abs())
+            .fold(0.0, f64::max)
+    };
+    assert!(difference(0.0) < 1e-12);
+    assert!(difference(0.5) > 0.001);
+    assert!(difference(1.0) > difference(0.5));
+}
+
+#[test]
+fn convolution_matches_direct_convolution() {
+    let signal: Vec<f64> = (0..5000u64)
+        .map(|index| ((index * 7919 % 1000) as f64 / 500.0 - 1.0) * 0.5)
+        .collect();
+    for ir_len in [1, 100, 3000] {
+        let impulse_response: Vec<f64> = (0..ir_len)
+            .map(|index| (-(index as f64) / 300.0).exp() * if index % 2 == 0 { 1.0 } else { -0.5 })
+            .collect();
+        let stream = signal.iter().enumerate().map(|(phase, value)| Sample {
+            data: Amplitude(*value),
+            phase: phase as u64,
+        });
+        let output: Vec<Sample<Amplitude>> = stream.convolve(impulse_response.clone()).collect();
+        assert_eq!(output.len(), signal.len() + ir_len - 1);
+        for (index, sample) in output.iter().enumerate().step_by(37) {
+            let expected: f64 = (0..ir_len)
+                .filter(|tap| *tap <= index && index - tap < signal.len())
+                .map(|tap| impulse_response[tap] * signal[index - tap])
+                .sum();
+            assert!(
+                (**sample - expected).abs() < 1e-9,
+                "{ir_len} taps, sample {index}"
+            );
+            assert_eq!(sample.phase, index as u64);
+        }
+    }
+}
+
+#[test]
+fn empty_impulse_response_passes_the_stream_through() {
+    let output: Vec<f64> = constant(0.5, 3)
+        .convolve(Vec::new())
+        .map(|sample| *sample)
+        .collect();
+    assert_eq!(output, [0.5; 3]);
+}
+
+#[test]
+fn waveshaper_shapes() {
+    assert_eq!(Shape::HardClip.apply(3.0), 1.0);
+    assert_eq!(Shape::HardClip.apply(-0.5), -0.5);
+    assert!((Shape::SoftClip.apply(0.5) - 0.5f64.tanh()).abs() < 1e-12);
+    assert!((Shape::Foldback.apply(1.5) - 0.5).abs() < 1e-12);
+    assert!((Shape::Foldback.apply(-2.5) - 0.5).abs() < 1e-12);
+    assert!(Shape::Tube.apply(10.0) > 0.99);
+    assert!(Shape::Tube.apply(-10.0) > -0.67);
+    assert_eq!(Shape::custom(|value| value * 2.0).apply(0.25), 0.5);
+    let gain = 3.0;
+    assert_eq!(Shape::custom(move |value| value * gain).apply(0.25), 0.75);
+}
+
+#[test]
+fn waveshaper_seeks_without_its_history() {
+    let shaper = || {
+        ConstantFrequencyGenerator::new(440.0)
+            .sine_wave(SAMPLE_RATE, 1.0)
+            .waveshaper(Shape::Tube, 4.0, 1.0, SAMPLE_RATE)
+            .oversampled(4)
+    };
+    let fresh: Vec<f64> = shaper().take(500).map(|sample| *sample).collect();
+    let mut seeked = shaper();
+    seeked.by_ref().take(1000).for_each(drop);
+    seeked.seek(SeekFrom::Start(0)).unwrap();
+    // The oscillator integrates its way back, which leaves a rounding error in its phase.
+    assert!(seeked
+        .take(500)
+        .zip(fresh)
+        .all(|(seeked, fresh)| (*seeked - fresh).abs() < 1e-9));
+}
+
+#[test]
+fn oversampled_waveshaper_aliases_less() {
+    // The 7th harmonic of a hard clipped 5kHz sine lands on 35kHz, which folds back to 9.1kHz.
+    let alias = |oversampling| {
+        let output: Vec<f64> = sine(5000.0)
+            .waveshaper(Shape::HardClip, 10.0, 1.0, SAMPLE_RATE)
+            .oversampled(oversampling)
+            .map(|sample| *sample)
+            .collect();
+        let omega = std::f64::consts::TAU * 9100.0 / SAMPLE_RATE as f64;
+        let (real, imaginary) =
+            output
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(real, imaginary), (index, value)| {
+                    let angle = omega * index as f64;
+                    (real + value * angle.cos(), imaginary + value * angle.sin())
+                });
+        f64::hypot(real, imaginary) / output.len() as f64
+    };
+    let plain = alias(1);
+    let oversampled = alias(8);
+    assert!(
+        decibels(oversampled / plain) < -12.0,
+        "{plain} {oversampled}"
+    );
+}
+
+#[test]
+fn bitcrusher_rounds_to_the_level_steps() {
+    let levels = |bits: f64| -> Vec<f64> {
+        let mut levels: Vec<f64> = sine(100.0).bitcrush(bits).map(|sample| *sample).collect();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup();
+        levels
+    };
+    // 2^3 levels, 2/7 apart from -1 to 1.
+    let output: Vec<f64> = sine(100.0).bitcrush(3.0).map(|sample| *sample).collect();
+    assert!(output.iter().all(|value| {
+        let level = (value + 1.0) * 3.5;
+        (level - level.round()).abs() < 1e-12
+    }));
+    assert_eq!(levels(3.0).len(), 8);
+    assert_eq!(levels(1.0), [-1.0, 1.0]);
+
+    let dithered = |seed| -> Vec<f64> {
+        sine(100.0)
+            .bitcrush(3.0)
+            .dithered(seed)
+            .map(|sample| *sample)
+            .collect()
+    };
+    assert_eq!(dithered(7), dithered(7));
+    assert_ne!(dithered(7), dithered(8));
+    assert_ne!(dithered(7), output);
+}
+
+#[test]
+fn sample_and_hold_holds_each_value() {
+    let ramp = (0..1000).map(|phase| Sample {
+        data: Amplitude(phase as f64),
+        phase,
+    });
+    let output: Vec<f64> = ramp
+        .sample_and_hold(100.0, 1000)
+        .map(|sample| *sample)
+        .collect();
+    assert_eq!(output[0..10], [0.0; 10]);
+    assert_eq!(output[10..20], [10.0; 10]);
+    assert_eq!(output[995], 990.0);
+
+    // A rate above the sample rate takes every value, and doesn't build up time that a lower
+    // rate would then have to work off.
+    let rates = std::iter::repeat_n(1e9, 100).chain(std::iter::repeat(100.0));
+    let ramp = (0..1000).map(|phase| Sample {
+        data: Amplitude(phase as f64),
+        phase,
+    });
+    let output: Vec<f64> = ramp
+        .sample_and_hold(Modulator::new(rates, 0.0), 1000)
+        .map(|sample| *sample)
+        .collect();
+    assert_eq!(output[99], 99.0);
+    assert_eq!(output[100..110], [100.0; 10]);
+    assert_eq!(output[110..120], [110.0; 10]);
+}
+
+fn constant(value: f64, len: u64) -> impl Iterator<Item = Sample<Amplitude>> {
+    (0..len).map(move |phase| Sample {
+        data: Amplitude(value),
+        phase,
+    })
+}
+
+/// `len` samples of silence, except for a 1.0 at sample `at`.
+fn impulse(at: u64, len: u64) -> impl Iterator<Item = Sample<Amplitude>> {
+    (0..len).map(move |phase| Sample {
+        data: Amplitude(if phase == at { 1.0 } else { 0.0 }),
+        phase,
+    })
+}
+
+#[test]
+fn compressor_settles_on_its_static_curve() {
+    let last = constant(0.5, 10000)
+        .compress(-12.0, 4.0, 0.001, 0.05, 0.0, SAMPLE_RATE)
+        .last()
+        .unwrap();
+    let expected = -12.0 + (decibels(0.5) + 12.0) / 4.0;
+    assert!(
+        (decibels(*last) - expected).abs() < 0.01,
+        "{}",
+        decibels(*last)
+    );
+
+    let below = constant(0.1, 10000)
+        .compress(-12.0, 4.0, 0.001, 0.05, 0.0, SAMPLE_RATE)
+        .last()
+        .unwrap();
+    assert!((*below - 0.1).abs() < 1e-12);
+}
+
+#[test]
+fn compressor_knee_is_continuous() {
+    let compressor = constant(0.0, 0).compress(-20.0, 3.0, 0.01, 0.1, 6.0, SAMPLE_RATE);
+    for level in [-23.0, -17.0] {
+        let inside = compressor.gain_reduction(level + 1e-9);
+        let outside = compressor.gain_reduction(level - 1e-9);
+        assert!(
+            (inside - outside).abs() < 1e-6,
+            "{level}: {inside} {outside}"
+        );
+    }
+    assert_eq!(compressor.gain_reduction(-30.0), 0.0);
+    assert!((compressor.gain_reduction(-10.0) - -10.0 * 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn hard_knee_compressor_is_finite_at_the_threshold() {
+    let compressor = constant(1.0, 0).compress(0.0, 4.0, 0.001, 0.05, 0.0, SAMPLE_RATE);
+    assert_eq!(compressor.gain_reduction(0.0), 0.0);
+    let output: Vec<_> = constant(1.0, 100)
+        .compress(0.0, 4.0, 0.001, 0.05, 0.0, SAMPLE_RATE)
+        .collect();
+    assert!(output.iter().all(|sample| **sample == 1.0));
+}
+
+#[test]
+fn limiter_keeps_every_sample_under_the_ceiling() {
+    let spiky = sine(440.0).map(|sample| Sample {
+        data: Amplitude(*sample * if sample.phase % 5000 < 100 { 8.0 } else { 0.5 }),
+        ..sample
+    });
+    let output: Vec<Sample<Amplitude>> = spiky.limit(-6.0, 0.005, 0.005, SAMPLE_RATE).collect();
+    assert_eq!(output.len(), SAMPLE_RATE as usize);
+    let ceiling = 10f64.powf(-6.0 / 20.0);
+    assert!(output.iter().all(|sample| sample.abs() <= ceiling));
+    assert!(output
+        .iter()
+        .enumerate()
+        .all(|(index, sample)| sample.phase == index as u64));
+    // Long after a spike the quiet part comes through untouched.
+    let quiet = sine(440.0).nth(4000).unwrap();
+    assert!((*output[4000] - *quiet * 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn noise_gate_silences_quiet_parts() {
+    let stream = sine(440.0).map(|sample| Sample {
+        data: Amplitude(*sample * if sample.phase < 22050 { 0.5 } else { 0.001 }),
+        ..sample
+    });
+    let output: Vec<f64> = stream
+        .noise_gate(-40.0, 0.001, 0.01, SAMPLE_RATE)
+        .map(|sample| *sample)
+        .collect();
+    assert!(output[11000..22050].iter().any(|value| value.abs() > 0.49));
+    assert!(output[30000..].iter().all(|value| value.abs() < 1e-6));
+}
+
+#[test]
+fn safeguard_corrects_and_reports_bad_samples() {
+    let values = [0.5, f64::NAN, -0.5, f64::INFINITY, 30.0, -2.0, 0.0];
+    let stream = values.iter().enumerate().map(|(phase, value)| Sample {
+        data: Amplitude(*value),
+        phase: phase as u64,
+    });
+    let corrections = Rc::new(RefCell::new(Vec::new()));
+    let seen = corrections.clone();
+    let mut safeguard = stream
+        .safeguard(SAMPLE_RATE)
+        .on_correction(move |correction, phase| seen.borrow_mut().push((correction, phase)));
+    let output: Vec<f64> = safeguard.by_ref().map(|sample| *sample).collect();
+
+    assert_eq!(output, [0.5, 0.0, -0.5, 0.0, 1.0, -1.0, 0.0]);
+    assert_eq!(
+        safeguard.report(),
+        SafeguardReport {
+            non_finite: 2,
+            clipped: 2,
+            dc_offset: 0,
+        }
+    );
+    assert_eq!(
+        *corrections.borrow(),
+        [
+            (Correction::NonFinite, 1),
+            (Correction::NonFinite, 3),
+            (Correction::Clipped, 4),
+            (Correction::Clipped, 5),
+        ]
+    );
+}
+
+#[test]
+fn safeguard_detects_dc() {
+    let mut safeguard = constant(0.6, SAMPLE_RATE).safeguard(SAMPLE_RATE);
+    assert_eq!(safeguard.by_ref().count(), SAMPLE_RATE as usize);
+    let report = safeguard.report();
+    assert!(report.dc_offset > SAMPLE_RATE * 9 / 10, "{report:?}");
+    assert!(!report.is_clean());
+    let mut clean = sine(440.0).safeguard(SAMPLE_RATE);
+    clean.by_ref().for_each(drop);
+    assert!(clean.report().is_clean());
+
+    // A 10% pulse has an average of -0.8, but it isn't stuck there.
+    let mut pulse = ConstantFrequencyGenerator::new(440.0)
+        .square_wave(SAMPLE_RATE, 0.1)
+        .take(SAMPLE_RATE as usize)
+        .safeguard(SAMPLE_RATE);
+    pulse.by_ref().for_each(drop);
+    assert!(pulse.report().is_clean(), "{:?}", pulse.report());
+}
+
+#[test]
+fn safeguard_starts_dc_detection_over_after_seeking() {
+    let detections = Rc::new(RefCell::new(0));
+    let seen = detections.clone();
+    // A square wave stuck at the start of its period is a constant.
+    let mut safeguard = ConstantFrequencyGenerator::new(0.0)
+        .square_wave(SAMPLE_RATE, 0.5)
+        .safeguard(SAMPLE_RATE)
+        .on_correction(move |correction, _| {
+            if correction == Correction::DcOffset {
+                *seen.borrow_mut() += 1;
+            }
+        });
+    safeguard.by_ref().take(4410).for_each(drop);
+    assert_eq!(*detections.borrow(), 1);
+
+    safeguard.seek(SeekFrom::Start(0)).unwrap();
+    let dc_offset = safeguard.report().dc_offset;
+    safeguard.next();
+    assert_eq!(safeguard.report().dc_offset, dc_offset);
+    safeguard.by_ref().take(4410).for_each(drop);
+    assert_eq!(*detections.borrow(), 2);
+}
+
+#[test]
+fn tremolo_moves_between_full_and_reduced_volume() {
+    for shape in [
+        TremoloShape::Sine,
+        TremoloShape::Triangle,
+        TremoloShape::Square,
+        TremoloShape::Sawtooth,
+    ] {
+        let output: Vec<f64> = constant(1.0, SAMPLE_RATE)
+            .tremolo(5.0, 0.75, shape, SAMPLE_RATE)
+            .map(|sample| *sample)
+            .collect();
+        let (quietest, loudest) = output
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(low, high), value| {
+                (low.min(*value), high.max(*value))
+            });
+        assert!((loudest - 1.0).abs() < 1e-3, "{shape:?}: {loudest}");
+        assert!((quietest - 0.25).abs() < 1e-3, "{shape:?}: {quietest}");
+    }
+}
+
+#[test]
+fn ring_mod_multiplies_by_the_carrier() {
+    let output: Vec<Sample<Amplitude>> = constant(0.5, 100).ring_mod(sine(1000.0)).collect();
+    assert_eq!(output.len(), 100);
+    for (output, carrier) in output.iter().zip(sine(1000.0)) {
+        assert!((**output - 0.5 * *carrier).abs() < 1e-12);
+        assert_eq!(output.phase, carrier.phase);
+    }
+}
+
+#[test]
+fn chorus_spreads_a_mono_stream_across_both_channels() {
+    let dry: Vec<Sample<Stereo>> = sine(440.0)
+        .chorus(3, 0.5, 0.005, 0.0, SAMPLE_RATE)
+        .collect();
+    for (output, input) in dry.iter().zip(sine(440.0)) {
+        assert_eq!(output.data, Stereo::from(input.data));
+    }
+
+    let wet: Vec<Sample<Stereo>> = sine(440.0)
+        .chorus(3, 0.5, 0.005, 0.7, SAMPLE_RATE)
+        .collect();
+    // Followed by the longest delay, 25ms.
+    assert_eq!(wet.len(), SAMPLE_RATE as usize + 1104);
+    assert!(wet
+        .iter()
+        .any(|sample| (sample.data.left - sample.data.right).abs() > 0.1));
+    assert!(wet
+        .iter()
+        .all(|sample| sample.data.left.abs() < 3.0 && sample.data.right.abs() < 3.0));
+}
+
+#[test]
+fn chorus_plays_its_voices_out_after_the_input_ends() {
+    // Without any sway every voice is 20ms, 882 samples, behind.
+    let output: Vec<Sample<Stereo>> = impulse(0, 10)
+        .chorus(2, 0.5, 0.0, 1.0, SAMPLE_RATE)
+        .collect();
+    assert!(output.len() > 882);
+    assert_eq!(output[882].phase, 882);
+    // Each voice is panned hard to one side, at `mix * √2 / voices`.
+    let level = std::f64::consts::SQRT_2 / 2.0;
+    assert!((output[882].data.left - level).abs() < 1e-9);
+    assert!((output[882].data.right - level).abs() < 1e-9);
+    assert!(output[1..882]
+        .iter()
+        .chain(&output[883..])
+        .all(|sample| sample.data.left.abs() < 1e-9 && sample.data.right.abs() < 1e-9));
+}
+
+#[test]
+fn stereo_safeguard_corrects_each_channel() {
+    let frames = [(0.5, f64::NAN), (2.0, -0.5), (0.0, -3.0)];
+    let stream = frames
+        .iter()
+        .enumerate()
+        .map(|(phase, (left, right))| Sample {
+            data: Stereo {
+                left: *left,
+                right: *right,
+            },
+            phase: phase as u64,
+        });
+    let mut safeguard = StereoFilterable::safeguard(stream, SAMPLE_RATE);
+    let output: Vec<Stereo> = safeguard.by_ref().map(|sample| sample.data).collect();
+    assert_eq!(
+        output,
+        [(0.5, 0.0), (1.0, -0.5), (0.0, -1.0)].map(|(left, right)| Stereo { left, right })
+    );
+    assert_eq!(
+        safeguard.report(),
+        SafeguardReport {
+            non_finite: 1,
+            clipped: 2,
+            dc_offset: 0,
+        }
+    );
+}
+
+#[test]
+fn widener_scales_the_side_channel() {
+    let stereo = || {
+        sine(440.0).zip(sine(660.0)).map(|(left, right)| Sample {
+            data: Stereo {
+                left: *left,
+                right: *right,
+            },
+            phase: left.phase,
+        })
+    };
+    for (input, mono) in stereo().zip(stereo().widen(0.0)) {
+        let mid = (input.data.left + input.data.right) / 2.0;
+        assert!((mono.data.left - mid).abs() < 1e-12 && (mono.data.right - mid).abs() < 1e-12);
+    }
+    for (input, unchanged) in stereo().zip(stereo().widen(1.0)) {
+        assert!((input.data.left - unchanged.data.left).abs() < 1e-12);
+        assert!((input.data.right - unchanged.data.right).abs() < 1e-12);
+    }
+    for (input, wide) in stereo().zip(stereo().widen(2.0)) {
+        let side = input.data.left - input.data.right;
+        assert!((wide.data.left - wide.data.right - 2.0 * side).abs() < 1e-12);
+    }
+}
+
+/// Peak amplitude of the samples in `range`.
+fn peak_in(samples: &[Sample<Amplitude>], range: std::ops::Range<usize>) -> f64 {
+    samples[range]
+        .iter()
+        .fold(0.0, |peak, sample| f64::max(peak, sample.abs()))
+}
+
+#[test]
+fn resonant_filters_peak_by_their_q_at_the_cutoff() {
+    for (q, gain) in [
+        (std::f64::consts::FRAC_1_SQRT_2, HALF_POWER_DB),
+        (4.0, 12.0412),
+    ] {
+        let lowpassed = settled_peak(sine(1000.0).resonant_lowpass(1000.0, q, SAMPLE_RATE));
+        let highpassed = settled_peak(sine(1000.0).resonant_highpass(1000.0, q, SAMPLE_RATE));
+        assert!(
+            (decibels(lowpassed) - gain).abs() < 0.05,
+            "Q {q}: {lowpassed}"
+        );
+        assert!(
+            (decibels(highpassed) - gain).abs() < 0.05,
+            "Q {q}: {highpassed}"
+        );
+    }
+}
+
+#[test]
+fn resonant_filters_pass_and_stop_far_from_the_cutoff() {
+    let q = std::f64::consts::FRAC_1_SQRT_2;
+    assert!(settled_peak(sine(100.0).resonant_lowpass(2000.0, q, SAMPLE_RATE)) > 0.99);
+    assert!(settled_peak(sine(10000.0).resonant_lowpass(1000.0, q, SAMPLE_RATE)) < 0.02);
+    assert!(settled_peak(sine(10000.0).resonant_highpass(500.0, q, SAMPLE_RATE)) > 0.99);
+    assert!(settled_peak(sine(100.0).resonant_highpass(1000.0, q, SAMPLE_RATE)) < 0.02);
+}
+
+#[test]
+fn resonant_filters_follow_a_cutoff_sweep() {
+    let q = std::f64::consts::FRAC_1_SQRT_2;
+    // The low-pass opens from 200Hz to 5kHz over the second, the high-pass closes the other way.
+    let opening = Sweep::exponential(200.0, 25.0, SAMPLE_RATE).clamped(200.0, 5000.0);
+    let closing = Sweep::exponential(5000.0, 1.0 / 25.0, SAMPLE_RATE).clamped(200.0, 5000.0);
+    let lowpassed: Vec<Sample<Amplitude>> = sine(2000.0)
+        .resonant_lowpass(opening, q, SAMPLE_RATE)
+        .collect();
+    let highpassed: Vec<Sample<Amplitude>> = sine(2000.0)
+        .resonant_highpass(closing, q, SAMPLE_RATE)
+        .collect();
+    assert!(peak_in(&lowpassed, 2000..4000) < 0.05);
+    assert!(peak_in(&lowpassed, 40000..44100) > 0.95);
+    assert!(peak_in(&highpassed, 1000..2000) < 0.25);
+    assert!(peak_in(&highpassed, 40000..44100) > 0.95);
+}
+
+#[test]
+fn highpass_follows_a_cutoff_sweep() {
+    let closing = Sweep::exponential(5000.0, 1.0 / 50.0, SAMPLE_RATE).clamped(50.0, 5000.0);
+    let highpassed: Vec<Sample<Amplitude>> = sine(500.0).highpass(closing, SAMPLE_RATE).collect();
+    assert!(peak_in(&highpassed, 2000..4000) < 0.2);
+    assert!(peak_in(&highpassed, 40000..44100) > 0.95);
+}
+
+#[test]
+fn exponential_slide_follows_a_per_sample_period_multiplier() {
+    // sfxr multiplies the period by `mult` every sample and adds `mult_slide` to `mult`.
+    let (mut mult, mult_slide) = (1.0002, -0.000000005);
+    let slide = -f64::ln(mult) * SAMPLE_RATE as f64;
+    let slide_delta = -mult_slide / mult * (SAMPLE_RATE as f64).powi(2);
+    let slid: Vec<f64> = ConstantFrequencyGenerator::new(1000.0)
+        .exponential_slide(slide, slide_delta, SAMPLE_RATE)
+        .take(SAMPLE_RATE as usize)
+        .map(|sample| *sample)
+        .collect();
+
+    let mut period = 1.0 / 1000.0;
+    for frequency in slid {
+        assert!((frequency * period - 1.0).abs() < 0.01);
+        period *= mult;
+        mult += mult_slide;
+    }
+}
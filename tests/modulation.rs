@@ -0,0 +1,94 @@
+use rsfxr::{
+    filter::FrequencyDomainFilterable,
+    generator::ConstantFrequencyGenerator,
+    modulation::{Modulation, Modulator, Sweep},
+};
+
+const SAMPLE_RATE: u64 = 100;
+
+fn values(modulation: &mut impl Modulation, len: usize) -> Vec<f64> {
+    (0..len).map(|_| modulation.next_value()).collect()
+}
+
+#[test]
+fn constant_never_changes() {
+    assert_eq!(values(&mut 0.25, 3), [0.25; 3]);
+}
+
+#[test]
+fn sweeps_move_at_their_rate_within_their_bounds() {
+    let linear = Sweep::linear(1.0, -2.0, SAMPLE_RATE);
+    assert_eq!(linear.value_at(0), 1.0);
+    assert!((linear.value_at(50) - 0.0).abs() < 1e-12);
+    assert!((linear.value_at(100) + 1.0).abs() < 1e-12);
+    let clamped = linear.clamped(0.5, -0.5);
+    assert_eq!(clamped.value_at(0), 0.5);
+    assert_eq!(clamped.value_at(100), -0.5);
+
+    let exponential = Sweep::exponential(100.0, 4.0, SAMPLE_RATE).clamped(0.0, 300.0);
+    assert!((exponential.value_at(50) - 200.0).abs() < 1e-9);
+    assert_eq!(exponential.value_at(200), 300.0);
+}
+
+#[test]
+fn exponential_sweep_with_a_rate_below_zero_stays_finite() {
+    for rate in [-2.0, 0.0, f64::NAN] {
+        let sweep = Sweep::exponential(100.0, rate, SAMPLE_RATE);
+        assert_eq!(sweep.value_at(0), 100.0);
+        assert_eq!(sweep.value_at(50), 0.0);
+    }
+}
+
+#[test]
+fn repeating_sweep_starts_over_with_the_repeat_filter() {
+    let sweep = Sweep::linear(0.0, 1.0, SAMPLE_RATE).repeating(4.0);
+    assert_eq!(sweep.period, 25);
+    assert_eq!(sweep.value_at(30), sweep.value_at(5));
+    let pitch: Vec<f64> = ConstantFrequencyGenerator::new(1.0)
+        .slide(1.0, 0.0, SAMPLE_RATE)
+        .repeat(4.0, SAMPLE_RATE)
+        .take(60)
+        .map(|sample| *sample)
+        .collect();
+    // Both start over on the same samples.
+    for position in 1..60 {
+        assert_eq!(
+            pitch[position] < pitch[position - 1],
+            sweep.value_at(position as u64) < sweep.value_at(position as u64 - 1),
+            "{position}"
+        );
+    }
+}
+
+#[test]
+fn sweep_seeks_to_a_position() {
+    let mut sweep = Sweep::linear(0.0, 1.0, SAMPLE_RATE);
+    let played = values(&mut sweep, 20);
+    sweep.seek_to(10);
+    assert_eq!(values(&mut sweep, 10), played[10..]);
+    assert_eq!(played[5], sweep.value_at(5));
+}
+
+#[test]
+fn modulator_keeps_its_last_value() {
+    let mut modulator = Modulator::new([0.1, 0.2].into_iter(), 0.5);
+    assert_eq!(values(&mut modulator, 4), [0.1, 0.2, 0.2, 0.2]);
+    let mut empty = Modulator::new(std::iter::empty(), 0.5);
+    assert_eq!(values(&mut empty, 2), [0.5, 0.5]);
+}
+
+#[test]
+fn square_wave_clamps_its_duty_cycle() {
+    let square = |duty_cycle: f64| -> Vec<f64> {
+        ConstantFrequencyGenerator::new(1.0)
+            .square_wave(SAMPLE_RATE, duty_cycle)
+            .take(SAMPLE_RATE as usize)
+            .map(|sample| *sample)
+            .collect()
+    };
+    assert_eq!(square(-1.0), square(0.01));
+    assert_eq!(square(0.0), square(0.01));
+    assert_eq!(square(0.01).iter().filter(|value| **value > 0.0).count(), 1);
+    assert_eq!(square(2.0), square(1.0));
+    assert!(square(1.0).iter().all(|value| *value == 1.0));
+}
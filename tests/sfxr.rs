@@ -0,0 +1,255 @@
+use rand::{rngs::StdRng, SeedableRng};
+use rsfxr::{
+    sfxr::{Param, SfxrParams, WaveType},
+    wave::AntiAliasing,
+};
+
+type Preset = fn(&mut StdRng) -> SfxrParams;
+
+const PRESETS: [(&str, Preset); 10] = [
+    ("pickup_coin", SfxrParams::pickup_coin),
+    ("laser_shoot", SfxrParams::laser_shoot),
+    ("explosion", SfxrParams::explosion),
+    ("powerup", SfxrParams::powerup),
+    ("hit_hurt", SfxrParams::hit_hurt),
+    ("jump", SfxrParams::jump),
+    ("blip_select", SfxrParams::blip_select),
+    ("synth", SfxrParams::synth),
+    ("tone", SfxrParams::tone),
+    ("random", SfxrParams::random),
+];
+
+fn bits(params: &SfxrParams) -> Vec<u64> {
+    params.render().map(|sample| sample.to_bits()).collect()
+}
+
+#[test]
+fn same_seed_renders_the_same_samples() {
+    for seed in 0..4 {
+        let first = SfxrParams::explosion(&mut StdRng::seed_from_u64(seed));
+        let second = SfxrParams::explosion(&mut StdRng::seed_from_u64(seed));
+        assert_eq!(first, second);
+        assert_eq!(bits(&first), bits(&second));
+    }
+}
+
+#[test]
+fn different_seed_renders_different_samples() {
+    let params = SfxrParams::explosion(&mut StdRng::seed_from_u64(0));
+    let reseeded = SfxrParams {
+        seed: params.seed.wrapping_add(1),
+        ..params
+    };
+    assert_ne!(bits(&params), bits(&reseeded));
+    assert_ne!(
+        SfxrParams::explosion(&mut StdRng::seed_from_u64(0)),
+        SfxrParams::explosion(&mut StdRng::seed_from_u64(1))
+    );
+}
+
+#[test]
+fn render_lasts_as_long_as_the_envelope() {
+    let params = SfxrParams::default();
+    // jsfxr's envelope lengths are `value² * 100000` samples at 44100 Hz, the envelope converts
+    // them to seconds and back, which can round the last sample away.
+    assert!(params.render().count().abs_diff(9000 + 16000) <= 1);
+    let half_rate = SfxrParams {
+        sample_rate: 22050,
+        ..params
+    };
+    assert!(half_rate.render().count().abs_diff((9000 + 16000) / 2) <= 1);
+}
+
+#[test]
+fn render_follows_the_envelope() {
+    let params = SfxrParams {
+        wave_type: WaveType::Sine,
+        env_attack: 0.2,
+        env_sustain: 0.3,
+        env_punch: 0.5,
+        env_decay: 0.2,
+        sound_vol: 0.3,
+        ..Default::default()
+    };
+    let samples: Vec<f64> = params.render().map(|sample| *sample).collect();
+    assert_eq!(samples.len(), 4000 + 9000 + 4000);
+    let peak = |from: usize, to: usize| {
+        samples[from..to]
+            .iter()
+            .fold(0.0_f64, |peak, sample| peak.max(sample.abs()))
+    };
+
+    let sustain = peak(4000, 13000);
+    let volume = params.sound_vol.exp() - 1.0;
+    // The punch starts the sustain at `1 + 2 * punch` and falls to 1 by the decay, without a step.
+    assert!((sustain - volume * 2.0).abs() < 0.01);
+    assert!((peak(12600, 13000) - volume).abs() < volume * 0.05);
+    assert!((peak(13000, 13400) - volume).abs() < volume * 0.05);
+    // Rising through the attack, falling through the decay.
+    assert!(peak(0, 400) < sustain * 0.15);
+    assert!(peak(3600, 4000) > peak(1800, 2200));
+    assert!(peak(13000, 13400) > peak(14800, 15200));
+    assert!(peak(16600, 17000) < sustain * 0.15);
+}
+
+#[test]
+fn render_reports_what_its_safeguard_corrected() {
+    let loud = SfxrParams {
+        env_punch: 1.0,
+        sound_vol: 1.0,
+        ..Default::default()
+    };
+    let mut rendered = loud.render();
+    assert!(rendered.by_ref().all(|sample| sample.abs() <= 1.0));
+    assert!(rendered.report().clipped > 0);
+
+    let mut quiet = SfxrParams::default().render().with_ceiling(0.1);
+    quiet.by_ref().for_each(drop);
+    assert!(quiet.report().clipped > 0);
+}
+
+#[test]
+fn random_sounds_render_finite_samples() {
+    for seed in 0..150 {
+        // A lower rate without supersampling keeps the test quick, the slides are the same.
+        let params = SfxrParams {
+            sample_rate: 11025,
+            anti_aliasing: AntiAliasing::None,
+            ..SfxrParams::random(&mut StdRng::seed_from_u64(seed))
+        };
+        assert!(
+            params.render().all(|sample| sample.is_finite()),
+            "seed {seed}"
+        );
+    }
+}
+
+#[test]
+fn mutate_leaves_locked_params_alone() {
+    let locked = [
+        Param::WaveType,
+        Param::BaseFreq,
+        Param::EnvSustain,
+        Param::EnvDecay,
+    ];
+    let mut rng = StdRng::seed_from_u64(0);
+    let original = SfxrParams::laser_shoot(&mut rng);
+    let mut params = original;
+    for _ in 0..100 {
+        params.mutate_locked(0.5, &locked, &mut rng);
+    }
+    assert_ne!(params, original);
+    for param in locked {
+        assert_eq!(params.get(param).to_bits(), original.get(param).to_bits());
+    }
+}
+
+#[test]
+fn mutate_keeps_params_in_range() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut params = SfxrParams::default();
+    let mut wave_types = Vec::new();
+    for _ in 0..200 {
+        params.mutate_unlocked(1.0, &Param::ALL, &mut rng);
+        for param in Param::ALL {
+            assert!(param.range().contains(&params.get(param)), "{param:?}");
+        }
+        wave_types.push(params.wave_type);
+    }
+    // Every wave type can be picked, not just jsfxr's first three.
+    assert!(wave_types.contains(&WaveType::BrownNoise));
+
+    params.mutate(1.0, &mut rng);
+    assert_eq!(params.wave_type, *wave_types.last().unwrap());
+}
+
+#[test]
+fn mutate_by_nothing_changes_nothing() {
+    let mut rng = StdRng::seed_from_u64(7);
+    // The random preset leaves some params outside their range, they must not be snapped back.
+    let original = SfxrParams::random(&mut rng);
+    assert!(Param::ALL
+        .into_iter()
+        .any(|param| !param.range().contains(&original.get(param))));
+    for amount in [0.0, f64::NAN] {
+        let mut params = original;
+        params.mutate_unlocked(amount, &Param::ALL, &mut rng);
+        assert_eq!(params, original);
+    }
+}
+
+#[test]
+fn same_seed_mutates_the_same_way() {
+    let mutated = |seed| {
+        let mut params = SfxrParams::default();
+        params.mutate(0.1, &mut StdRng::seed_from_u64(seed));
+        params
+    };
+    assert_eq!(mutated(3), mutated(3));
+    assert_ne!(mutated(3), mutated(4));
+}
+
+#[test]
+fn repeat_restarts_the_duty_sweep() {
+    let params = SfxrParams {
+        duty: 0.0,
+        duty_ramp: 1.0,
+        repeat_speed: 0.5,
+        anti_aliasing: AntiAliasing::None,
+        ..Default::default()
+    };
+    // jsfxr repeats every `(1 - speed)² * 20000 + 32` samples.
+    let period = 5032;
+    let samples: Vec<f64> = params
+        .render()
+        .with_ceiling(f64::INFINITY)
+        .map(|sample| *sample)
+        .collect();
+    let volume = params.sound_vol.exp() - 1.0;
+    let positive = |from: usize| {
+        samples[from..from + 1000]
+            .iter()
+            .filter(|sample| **sample > volume * 0.5)
+            .count()
+    };
+    // The duty sweeps down within a repeat, and starts from the top again on the next one.
+    assert!(positive(3000) < positive(0));
+    assert!(positive(period) > positive(3000));
+    assert!(positive(period + 3000) < positive(period));
+}
+
+#[test]
+fn presets_render_the_same_finite_sound_for_a_seed() {
+    for (name, preset) in PRESETS {
+        for seed in 0..3 {
+            let params = preset(&mut StdRng::seed_from_u64(seed));
+            assert_eq!(params, preset(&mut StdRng::seed_from_u64(seed)), "{name}");
+            let rendered = bits(&params);
+            assert!(!rendered.is_empty(), "{name} {seed}");
+            assert!(
+                rendered
+                    .iter()
+                    .all(|bits| f64::from_bits(*bits).is_finite()),
+                "{name} {seed}"
+            );
+            assert_eq!(rendered, bits(&params), "{name} {seed}");
+        }
+    }
+}
+
+#[test]
+fn presets_rarely_clip() {
+    for (name, preset) in PRESETS {
+        if name == "random" {
+            continue;
+        }
+        let clipped = (0..20)
+            .filter(|seed| {
+                let mut rendered = preset(&mut StdRng::seed_from_u64(*seed)).render();
+                rendered.by_ref().for_each(drop);
+                rendered.report().clipped > 0
+            })
+            .count();
+        assert!(clipped <= 2, "{name}: {clipped} of 20 clipped");
+    }
+}
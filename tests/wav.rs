@@ -0,0 +1,241 @@
+use std::io::Cursor;
+
+use rsfxr::{
+    filter::{convolution::ImpulseResponse, AmplitudeDomainFilterable},
+    wav::{self, SampleFormat, WavSpec, WavWriter},
+    Amplitude, Sample, Stereo,
+};
+
+fn stream(values: &[f64]) -> impl Iterator<Item = Sample<Amplitude>> + '_ {
+    values.iter().enumerate().map(|(phase, value)| Sample {
+        data: Amplitude(*value),
+        phase: phase as u64,
+    })
+}
+
+#[test]
+fn read_returns_what_write_wrote() {
+    let values = [0.0, 0.5, -0.5, 1.0, -1.0, 0.25, -0.125];
+    for (format, tolerance) in [
+        (SampleFormat::U8, 1.0 / 127.0),
+        (SampleFormat::I16, 1.0 / 32767.0),
+        (SampleFormat::I24, 1.0 / 8388607.0),
+        (SampleFormat::F32, 1e-7),
+    ] {
+        let spec = WavSpec {
+            sample_rate: 22050,
+            format,
+        };
+        let mut file = Vec::new();
+        wav::write(&mut file, spec, stream(&values)).unwrap();
+        let read = wav::read(Cursor::new(file)).unwrap();
+        assert_eq!(read.spec, spec);
+        assert_eq!(read.channels, 1);
+        assert_eq!(read.samples.len(), values.len());
+        for (read, written) in read.samples.iter().zip(values) {
+            assert!(
+                (read - written).abs() <= tolerance,
+                "{format:?}: {read} {written}"
+            );
+        }
+    }
+}
+
+#[test]
+fn channel_splits_stereo_files() {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::F32,
+    };
+    let mut file = Vec::new();
+    wav::write(
+        &mut file,
+        spec,
+        stream(&[0.1, 0.2, 0.3]).zip(stream(&[-0.1, -0.2, -0.3])),
+    )
+    .unwrap();
+    let read = wav::read(Cursor::new(file)).unwrap();
+    assert_eq!(read.channels, 2);
+    let right = read.channel(1);
+    assert_eq!(right.len(), 3);
+    assert!((right[2] + 0.3).abs() < 1e-7);
+}
+
+#[test]
+fn impulse_response_loads_from_a_wav_file() {
+    let path = std::env::temp_dir().join(format!("rsfxr-impulse-{}.wav", std::process::id()));
+    let spec = WavSpec {
+        sample_rate: 44100,
+        format: SampleFormat::F32,
+    };
+    wav::save(&path, spec, stream(&[0.0, 0.0, 0.5])).unwrap();
+
+    assert!(ImpulseResponse::load(&path, 48000).is_err());
+    let impulse_response = ImpulseResponse::load(&path, 44100).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(impulse_response.len(), 3);
+
+    let output: Vec<f64> = stream(&[1.0, -1.0])
+        .convolve(impulse_response)
+        .map(|sample| *sample)
+        .collect();
+    let expected = [0.0, 0.0, 0.5, -0.5];
+    assert_eq!(output.len(), expected.len());
+    for (output, expected) in output.iter().zip(expected) {
+        assert!((output - expected).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn stereo_samples_write_both_channels() {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::I16,
+    };
+    let frames = [(0.5, -0.25), (-1.0, 1.0)].map(|(left, right)| Sample {
+        data: Stereo { left, right },
+        phase: 0,
+    });
+    let mut file = Vec::new();
+    wav::write(&mut file, spec, frames).unwrap();
+    let read = wav::read(Cursor::new(file)).unwrap();
+    assert_eq!(read.channels, 2);
+    let expected = [0.5, -0.25, -1.0, 1.0];
+    for (read, written) in read.samples.iter().zip(expected) {
+        assert!((read - written).abs() < 1.0 / 32767.0);
+    }
+}
+
+#[test]
+fn writer_writes_the_same_file_as_write() {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::U8,
+    };
+    // An odd number of bytes, so the data chunk needs padding.
+    let values = [0.5, -0.5, 1.0];
+    let mut written = Vec::new();
+    wav::write(&mut written, spec, stream(&values)).unwrap();
+
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), spec).unwrap();
+    for sample in stream(&values) {
+        writer.write_frame(&sample).unwrap();
+    }
+    let streamed = writer.finish().unwrap().into_inner();
+    assert_eq!(streamed, written);
+    assert_eq!(streamed.len(), 44 + 4);
+    assert_eq!(&streamed[4..8], &(36 + 4u32).to_le_bytes());
+    assert_eq!(&streamed[40..44], &3u32.to_le_bytes());
+}
+
+#[test]
+fn writer_takes_its_channels_from_the_frame() {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::F32,
+    };
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), spec).unwrap();
+    for frame in stream(&[0.1, 0.2]).zip(stream(&[-0.1, -0.2])) {
+        writer.write_frame(&frame).unwrap();
+    }
+    let read = wav::read(Cursor::new(writer.finish().unwrap().into_inner())).unwrap();
+    assert_eq!(read.channels, 2);
+    assert_eq!(read.channel(0).len(), 2);
+    assert!((read.channel(1)[1] + 0.2).abs() < 1e-7);
+}
+
+/// A mono 16-bit file with `chunk` inserted between the fmt and the data chunk.
+fn with_chunk(chunk: &[u8]) -> Vec<u8> {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::I16,
+    };
+    let mut file = Vec::new();
+    wav::write(&mut file, spec, stream(&[0.5, -0.5])).unwrap();
+    file.splice(36..36, chunk.iter().copied());
+    file
+}
+
+#[test]
+fn read_skips_unknown_chunks() {
+    let read = wav::read(Cursor::new(with_chunk(b"LIST\x03\x00\x00\x00abc\x00"))).unwrap();
+    assert_eq!(read.samples.len(), 2);
+    assert!((read.samples[1] + 0.5).abs() < 1.0 / 32767.0);
+}
+
+#[test]
+fn read_fails_on_chunks_longer_than_the_file() {
+    let error = wav::read(Cursor::new(with_chunk(b"LIST\xff\xff\xff\xff"))).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let mut file = with_chunk(&[]);
+    file[40..44].copy_from_slice(&1000u32.to_le_bytes());
+    let error = wav::read(Cursor::new(file)).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn read_takes_an_unknown_data_size_to_the_end_of_the_file() {
+    let mut file = with_chunk(&[]);
+    file[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+    let read = wav::read(Cursor::new(file)).unwrap();
+    assert_eq!(read.samples.len(), 2);
+    assert!((read.samples[1] + 0.5).abs() < 1.0 / 32767.0);
+}
+
+#[test]
+fn float_files_have_a_fact_chunk() {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::F32,
+    };
+    let mut file = Vec::new();
+    wav::write(
+        &mut file,
+        spec,
+        stream(&[0.1, 0.2, 0.3]).zip(stream(&[-0.1, -0.2, -0.3])),
+    )
+    .unwrap();
+    assert_eq!(file.len(), 58 + 3 * 2 * 4);
+    assert_eq!(&file[4..8], &(50 + 3 * 2 * 4u32).to_le_bytes());
+    // An 18 byte fmt chunk without extra format bytes.
+    assert_eq!(&file[16..20], &18u32.to_le_bytes());
+    assert_eq!(&file[36..38], &0u16.to_le_bytes());
+    // The number of frames.
+    assert_eq!(&file[38..42], b"fact");
+    assert_eq!(&file[46..50], &3u32.to_le_bytes());
+    assert_eq!(&file[50..54], b"data");
+
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), spec).unwrap();
+    for frame in stream(&[0.1, 0.2, 0.3]).zip(stream(&[-0.1, -0.2, -0.3])) {
+        writer.write_frame(&frame).unwrap();
+    }
+    assert_eq!(writer.finish().unwrap().into_inner(), file);
+    assert_eq!(wav::read(Cursor::new(file)).unwrap().samples.len(), 6);
+}
+
+#[test]
+fn write_passes_every_channel_through_a_safeguard() {
+    let spec = WavSpec {
+        sample_rate: 8000,
+        format: SampleFormat::F32,
+    };
+    let mut file = Vec::new();
+    let report = wav::write(
+        &mut file,
+        spec,
+        stream(&[0.5, 2.0, f64::NAN]).zip(stream(&[-3.0, 0.25, 0.0])),
+    )
+    .unwrap();
+    assert_eq!(report.clipped, 2);
+    assert_eq!(report.non_finite, 1);
+    let read = wav::read(Cursor::new(file)).unwrap();
+    assert_eq!(read.samples, [0.5, -1.0, 1.0, 0.25, 0.0, 0.0]);
+
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), spec).unwrap();
+    for sample in stream(&[0.5, -2.0, f64::INFINITY]) {
+        writer.write_frame(&sample).unwrap();
+    }
+    assert_eq!(writer.report().clipped, 1);
+    assert_eq!(writer.report().non_finite, 1);
+}
@@ -0,0 +1,255 @@
+use std::io::{Seek, SeekFrom};
+
+use rsfxr::{
+    filter::FrequencyDomainFilterable, generator::ConstantFrequencyGenerator, wave::AntiAliasing,
+    Amplitude, Sample,
+};
+
+const SAMPLE_RATE: u64 = 44100;
+
+/// A period of exactly 512 samples, so seeking lands on the same phase as playing.
+const FREQUENCY: f64 = SAMPLE_RATE as f64 / 512.0;
+
+/// A sine sliding linearly from 100Hz to 1100Hz over a second.
+fn sliding_sine() -> impl Iterator<Item = Sample<Amplitude>> + Seek {
+    ConstantFrequencyGenerator::new(100.0)
+        .slide(1000.0, 0.0, SAMPLE_RATE)
+        .sine_wave(SAMPLE_RATE, 1.0)
+}
+
+fn values(samples: impl Iterator<Item = Sample<Amplitude>>) -> Vec<f64> {
+    samples.map(|sample| *sample).collect()
+}
+
+/// Number of times the value changes from one sample to the next.
+fn changes(values: &[f64]) -> usize {
+    values.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+#[test]
+fn noise_picks_values_per_period_values_every_period() {
+    let once: Vec<f64> = ConstantFrequencyGenerator::new(FREQUENCY)
+        .noise_wave(SAMPLE_RATE, 1)
+        .take(SAMPLE_RATE as usize)
+        .map(|sample| *sample)
+        .collect();
+    let often: Vec<f64> = ConstantFrequencyGenerator::new(FREQUENCY)
+        .noise_wave(SAMPLE_RATE, 1)
+        .values_per_period(4)
+        .take(SAMPLE_RATE as usize)
+        .map(|sample| *sample)
+        .collect();
+    assert!(changes(&once) == 86);
+    assert!(changes(&often) == 344);
+
+    let mut seeked = ConstantFrequencyGenerator::new(FREQUENCY)
+        .noise_wave(SAMPLE_RATE, 1)
+        .values_per_period(4);
+    seeked.seek(SeekFrom::Start(20000)).unwrap();
+    let seeked: Vec<f64> = seeked.take(1000).map(|sample| *sample).collect();
+    assert_eq!(seeked, often[20000..21000]);
+}
+
+#[test]
+fn oscillators_integrate_a_sliding_frequency() {
+    let output = values(sliding_sine().take(SAMPLE_RATE as usize));
+    // The phase is the integral of the frequency, 100 + 1000 / 2 periods in the first second.
+    let rising = output
+        .windows(2)
+        .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+        .count();
+    assert!((599..=600).contains(&rising), "{rising}");
+    // No jumps, the wave never moves further than a sine at the top frequency can.
+    let max_step = std::f64::consts::TAU * 1100.0 / SAMPLE_RATE as f64;
+    assert!(output
+        .windows(2)
+        .all(|pair| (pair[1] - pair[0]).abs() <= max_step));
+}
+
+#[test]
+fn oscillators_seek_to_the_same_phase() {
+    let played = values(sliding_sine().take(30000));
+    let close = |seeked: &[f64], from: usize| {
+        seeked
+            .iter()
+            .zip(&played[from..])
+            .all(|(seeked, played)| (seeked - played).abs() < 1e-6)
+    };
+
+    let mut forward = sliding_sine();
+    forward.seek(SeekFrom::Start(20000)).unwrap();
+    assert!(close(&values(forward.take(1000)), 20000));
+
+    let mut backward = sliding_sine();
+    backward.by_ref().take(25000).for_each(drop);
+    backward.seek(SeekFrom::Start(5000)).unwrap();
+    assert!(close(&values(backward.by_ref().take(1000)), 5000));
+    backward.seek(SeekFrom::Current(10000)).unwrap();
+    assert!(close(&values(backward.take(1000)), 16000));
+}
+
+/// The share of the energy of `samples`, `periods` periods of a wave, that isn't at its harmonics,
+/// i.e. what aliased.
+fn aliasing(samples: &[f64], periods: usize) -> f64 {
+    let len = samples.len();
+    let bin_power = |bin: usize| {
+        let (real, imaginary) =
+            samples
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(real, imaginary), (index, value)| {
+                    let angle = std::f64::consts::TAU * (bin * index) as f64 / len as f64;
+                    (real + value * angle.cos(), imaginary - value * angle.sin())
+                });
+        real * real + imaginary * imaginary
+    };
+    // Parseval, both halves of the spectrum hold the harmonics except for DC.
+    let total = len as f64 * samples.iter().map(|value| value * value).sum::<f64>();
+    let harmonics = bin_power(0)
+        + 2.0
+            * (1..)
+                .map(|harmonic| harmonic * periods)
+                .take_while(|bin| 2 * bin < len)
+                .map(bin_power)
+                .sum::<f64>();
+    1.0 - harmonics / total
+}
+
+#[test]
+fn anti_aliased_oscillators_alias_less() {
+    // 301 periods in 4410 samples, with seven harmonics under the Nyquist frequency.
+    let frequency = 3010.0;
+    let len = 4410;
+    let render = |square: bool, anti_aliasing: AntiAliasing| -> Vec<f64> {
+        let generator = ConstantFrequencyGenerator::new(frequency);
+        if square {
+            values(
+                generator
+                    .square_wave(SAMPLE_RATE, 0.5)
+                    .anti_aliased(anti_aliasing)
+                    .take(len),
+            )
+        } else {
+            values(
+                generator
+                    .sawtooth_wave(SAMPLE_RATE, 1.0)
+                    .anti_aliased(anti_aliasing)
+                    .take(len),
+            )
+        }
+    };
+    for square in [false, true] {
+        let naive = aliasing(&render(square, AntiAliasing::None), 301);
+        let poly_blep = aliasing(&render(square, AntiAliasing::PolyBlep), 301);
+        let supersampled = aliasing(&render(square, AntiAliasing::Supersample(8)), 301);
+        assert!(poly_blep < naive / 10.0, "{naive} {poly_blep}");
+        assert!(supersampled < naive / 4.0, "{naive} {supersampled}");
+    }
+}
+
+/// Two periods of `FREQUENCY`.
+fn two_periods(wave: impl Iterator<Item = Sample<Amplitude>>) -> Vec<f64> {
+    values(wave.take(1024))
+}
+
+#[test]
+fn waveforms_repeat_every_period_within_range() {
+    let tone = || ConstantFrequencyGenerator::new(FREQUENCY);
+    let waves = [
+        two_periods(tone().triangle_wave(SAMPLE_RATE)),
+        two_periods(tone().breaker_wave(SAMPLE_RATE)),
+        two_periods(tone().tan_wave(SAMPLE_RATE)),
+        two_periods(tone().whistle_wave(SAMPLE_RATE)),
+    ];
+    for wave in &waves {
+        assert!(wave.iter().all(|value| (-1.0..=1.0).contains(value)));
+        assert!((0..512).all(|index| (wave[index] - wave[index + 512]).abs() < 1e-12));
+    }
+
+    let [triangle, breaker, tan, whistle] = waves;
+    // Quarter periods are 128 samples.
+    assert_eq!(
+        [triangle[0], triangle[128], triangle[256]],
+        [-1.0, 0.0, 1.0]
+    );
+    assert!(triangle
+        .windows(2)
+        .all(|pair| ((pair[1] - pair[0]).abs() - 4.0 / 512.0).abs() < 1e-12));
+    assert!((breaker[0] - 0.75).abs() < 1e-12);
+    assert!((tan[64] - (std::f64::consts::PI / 8.0).tan()).abs() < 1e-12);
+    assert!(tan[129..384].iter().all(|value| value.abs() == 1.0));
+    let sine = |at: f64| (std::f64::consts::TAU * at).sin();
+    assert!(
+        (whistle[100] - (0.75 * sine(100.0 / 512.0) + 0.25 * sine(2000.0 / 512.0))).abs() < 1e-9
+    );
+}
+
+/// How much every value of `samples` follows the one before, from `-1.0` to `1.0`.
+fn correlation(samples: &[f64]) -> f64 {
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    let variance = samples
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>();
+    let covariance = samples
+        .windows(2)
+        .map(|pair| (pair[0] - mean) * (pair[1] - mean))
+        .sum::<f64>();
+    covariance / variance
+}
+
+#[test]
+fn noise_generators_are_seeded_and_coloured() {
+    // A new value on every sample.
+    let noise = || ConstantFrequencyGenerator::new(SAMPLE_RATE as f64);
+    let len = SAMPLE_RATE as usize;
+    let white = |seed| values(noise().noise_wave(SAMPLE_RATE, seed).take(len));
+    let pink = |seed| values(noise().pink_noise(SAMPLE_RATE, seed).take(len));
+    let brown = |seed| values(noise().brown_noise(SAMPLE_RATE, seed).take(len));
+
+    let colours: [&dyn Fn(u64) -> Vec<f64>; 3] = [&white, &pink, &brown];
+    for colour in colours {
+        let noise = colour(3);
+        assert!(noise.iter().all(|value| (-1.0..=1.0).contains(value)));
+        assert_eq!(noise, colour(3));
+        assert_ne!(noise, colour(4));
+    }
+    let (white, pink, brown) = (
+        correlation(&white(3)),
+        correlation(&pink(3)),
+        correlation(&brown(3)),
+    );
+    assert!(white.abs() < 0.05, "{white}");
+    assert!(pink > 0.5, "{pink}");
+    assert!(brown > 0.9, "{brown}");
+}
+
+#[test]
+fn brown_noise_seeks_to_the_same_value() {
+    let brown = || {
+        ConstantFrequencyGenerator::new(FREQUENCY)
+            .brown_noise(SAMPLE_RATE, 5)
+            .values_per_period(32)
+    };
+    let played = values(brown().take(30000));
+    let mut seeked = brown();
+    seeked.seek(SeekFrom::Start(20000)).unwrap();
+    // Seeking rebuilds the integrator from the last steps only, the older ones have decayed.
+    assert!(values(seeked.take(1000))
+        .iter()
+        .zip(&played[20000..21000])
+        .all(|(seeked, played)| (seeked - played).abs() < 1e-3));
+}
+
+#[test]
+fn bit_noise_repeats_after_32767_steps() {
+    let noise = values(
+        ConstantFrequencyGenerator::new(SAMPLE_RATE as f64)
+            .bit_noise(SAMPLE_RATE)
+            .take(40000),
+    );
+    assert!(noise.iter().all(|value| value.abs() == 1.0));
+    assert_eq!(noise[..7000], noise[32767..39767]);
+    assert_ne!(noise[..7000], noise[1..7001]);
+    assert!(correlation(&noise).abs() < 0.05);
+}